@@ -75,9 +75,18 @@ pub mod net;
 ///pub mod can ;  
 /// gw.rs a raw version of the gateway 
 ///pub mod gw ;
+pub mod socketcan_capture;
 pub mod socketcan_frame ; 
 pub mod socketcan_id ; 
+pub mod socketcan_j1939;
+pub mod socketcan_load;
+pub mod socketcan_mock;
+pub mod socketcan_queue;
+pub mod socketcan_router;
 pub mod socketcan_embedded;
+pub mod socketcan_error;
+pub mod socketcan_filter;
+pub mod socketcan_signal;
 pub mod pages;
 #[cfg(CONFIG_PCI)]
 pub mod pci;
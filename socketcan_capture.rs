@@ -0,0 +1,278 @@
+// Implements analysis helpers over captures of CAN frames.
+//
+// This file is part of the Rust 'socketcan-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+
+//! Analysis of CAN bus captures.
+//!
+//! These helpers operate on a capture, which is simply a slice of frames
+//! in the order in which they were received. They are meant for offline
+//! comparison and diagnostics rather than the receive path.
+//!
+//! Helpers that need to collect results allocate, and report allocation
+//! failures through the kernel [`Result`].
+
+use crate::error::Result;
+use crate::socketcan_embedded::Frame;
+use crate::socketcan_id::*;
+use alloc::vec::Vec;
+use core::time::Duration;
+
+/// Gets the distinct IDs that appear in a capture.
+///
+/// The IDs are returned sorted by their bus priority, using the `Ord`
+/// implementation of `Id`.
+pub fn distinct_ids(frames: &[impl Frame]) -> Result<Vec<Id>> {
+    let mut ids = Vec::try_with_capacity(frames.len())?;
+    for frame in frames {
+        ids.try_push(frame.id())?;
+    }
+    ids.sort_unstable();
+    ids.dedup();
+    Ok(ids)
+}
+
+/// Gets the IDs that appear in only one of two captures.
+///
+/// This returns the IDs that are only in `a`, and those that are only in
+/// `b`. Each `Vec` is sorted by bus priority and holds every ID at most
+/// once, so it can be used as an ordered set. Sorted vectors are returned
+/// rather than `BTreeSet`s because `BTreeSet::insert()` can't report an
+/// allocation failure, while `Vec::try_push()` can.
+///
+/// # Examples
+///
+/// ```
+/// # use kernel::socketcan_capture::id_diff;
+/// # use kernel::socketcan_embedded::Frame;
+/// # use kernel::socketcan_frame::CanDataFrame;
+/// # use kernel::socketcan_id::{Id, StandardId};
+/// let id = |raw| StandardId::new(raw).unwrap();
+/// let frame = |raw| CanDataFrame::new(id(raw), &[]).unwrap();
+///
+/// let a = [frame(0x200), frame(0x100), frame(0x100)];
+/// let b = [frame(0x200), frame(0x300)];
+///
+/// let (only_a, only_b) = id_diff(&a, &b).unwrap();
+/// assert_eq!(only_a, [Id::Standard(id(0x100))]);
+/// assert_eq!(only_b, [Id::Standard(id(0x300))]);
+/// ```
+pub fn id_diff(a: &[impl Frame], b: &[impl Frame]) -> Result<(Vec<Id>, Vec<Id>)> {
+    let a = distinct_ids(a)?;
+    let b = distinct_ids(b)?;
+
+    let mut only_a = Vec::new();
+    let mut only_b = Vec::new();
+
+    let (mut ia, mut ib) = (a.iter().peekable(), b.iter().peekable());
+    loop {
+        match (ia.peek(), ib.peek()) {
+            (Some(x), Some(y)) if x < y => only_a.try_push(*ia.next().unwrap())?,
+            (Some(x), Some(y)) if x > y => only_b.try_push(*ib.next().unwrap())?,
+            (Some(_), Some(_)) => {
+                ia.next();
+                ib.next();
+            }
+            (Some(_), None) => only_a.try_push(*ia.next().unwrap())?,
+            (None, Some(_)) => only_b.try_push(*ib.next().unwrap())?,
+            (None, None) => break,
+        }
+    }
+    Ok((only_a, only_b))
+}
+
+/// The bytes of the payload that changed for an ID over a capture.
+#[derive(Debug, Clone)]
+pub struct ChangeMask {
+    /// The ID of the frames
+    pub id: Id,
+    /// The number of frames with this ID in the capture
+    pub count: usize,
+    /// A bit is set for each bit of the payload that changed between
+    /// successive frames with this ID. Only the first 64 bytes of longer
+    /// payloads are tracked.
+    pub mask: [u8; 64],
+    /// Whether the length of the payload changed between frames.
+    pub len_changed: bool,
+    last: [u8; 64],
+    last_len: usize,
+}
+
+impl ChangeMask {
+    fn new(id: Id, data: &[u8]) -> Self {
+        let mut last = [0u8; 64];
+        let n = data.len().min(last.len());
+        last[..n].copy_from_slice(&data[..n]);
+        Self {
+            id,
+            count: 1,
+            mask: [0u8; 64],
+            len_changed: false,
+            last,
+            last_len: data.len(),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        self.count += 1;
+        if data.len() != self.last_len {
+            self.len_changed = true;
+        }
+        let mut next = [0u8; 64];
+        let n = data.len().min(next.len());
+        next[..n].copy_from_slice(&data[..n]);
+        for (m, (a, b)) in self.mask.iter_mut().zip(self.last.iter().zip(next.iter())) {
+            *m |= a ^ b;
+        }
+        self.last = next;
+        self.last_len = data.len();
+    }
+
+    /// Whether every frame with this ID had a byte-identical payload.
+    pub fn is_unchanged(&self) -> bool {
+        !self.len_changed && self.mask.iter().all(|b| *b == 0)
+    }
+}
+
+/// Gets the bits of the payload that changed for each ID in a capture.
+///
+/// The result has one entry per distinct ID, sorted by bus priority.
+pub fn change_masks(frames: &[impl Frame]) -> Result<Vec<ChangeMask>> {
+    let mut masks: Vec<ChangeMask> = Vec::new();
+    for frame in frames {
+        let id = frame.id();
+        match masks.iter_mut().find(|m| m.id == id) {
+            Some(m) => m.update(frame.data()),
+            None => masks.try_push(ChangeMask::new(id, frame.data()))?,
+        }
+    }
+    masks.sort_unstable_by_key(|m| m.id);
+    Ok(masks)
+}
+
+/// Gets the IDs with a payload that never changed over a capture.
+///
+/// An ID is considered stuck if it appeared at least `min_occurrences`
+/// times, with a byte-identical payload every time. This can indicate a
+/// faulty sensor or a node that stopped updating its data.
+///
+/// # Examples
+///
+/// ```
+/// # use kernel::socketcan_capture::stuck_ids;
+/// # use kernel::socketcan_embedded::Frame;
+/// # use kernel::socketcan_frame::CanDataFrame;
+/// # use kernel::socketcan_id::{Id, StandardId};
+/// let id = |raw| StandardId::new(raw).unwrap();
+///
+/// let capture = [
+///     CanDataFrame::new(id(0x100), &[1, 2]).unwrap(),
+///     CanDataFrame::new(id(0x200), &[0]).unwrap(),
+///     CanDataFrame::new(id(0x100), &[1, 2]).unwrap(),
+///     CanDataFrame::new(id(0x200), &[1]).unwrap(),
+///     CanDataFrame::new(id(0x100), &[1, 2]).unwrap(),
+/// ];
+///
+/// let ids = stuck_ids(&capture, 3).unwrap();
+/// assert_eq!(ids, [Id::Standard(id(0x100))]);
+/// ```
+pub fn stuck_ids(frames: &[impl Frame], min_occurrences: usize) -> Result<Vec<Id>> {
+    let mut ids = Vec::new();
+    for m in change_masks(frames)? {
+        if m.count >= min_occurrences && m.is_unchanged() {
+            ids.try_push(m.id)?;
+        }
+    }
+    Ok(ids)
+}
+
+/// Finds the frame in a timestamped capture that best matches a frame in
+/// another capture.
+///
+/// This is the frame with the same ID that is closest in time to `t`, as
+/// long as it is within `window` of it.
+fn closest_match<F: Frame>(
+    capture: &[(Duration, F)],
+    id: Id,
+    t: Duration,
+    window: Duration,
+) -> Option<&F> {
+    let dist = |ts: Duration| if ts > t { ts - t } else { t - ts };
+    capture
+        .iter()
+        .filter(|(ts, frame)| frame.id() == id && dist(*ts) <= window)
+        .min_by_key(|(ts, _)| dist(*ts))
+        .map(|(_, frame)| frame)
+}
+
+/// Compares two timestamped captures, reporting where each ID diverges.
+///
+/// Each frame in one capture is matched with the frame having the same ID
+/// that is closest in time in the other capture, within `window`. A frame
+/// diverges if there is no such match, or if the match has a different
+/// payload.
+///
+/// The result holds the first divergence for each ID, as the ID and the
+/// timestamp of the diverging frame, sorted by time.
+///
+/// # Examples
+///
+/// ```
+/// # use core::time::Duration;
+/// # use kernel::socketcan_capture::aligned_diff;
+/// # use kernel::socketcan_embedded::Frame;
+/// # use kernel::socketcan_frame::CanDataFrame;
+/// # use kernel::socketcan_id::{Id, StandardId};
+/// let id = |raw| StandardId::new(raw).unwrap();
+/// let ms = Duration::from_millis;
+///
+/// let a = [
+///     (ms(0), CanDataFrame::new(id(0x100), &[1]).unwrap()),
+///     (ms(10), CanDataFrame::new(id(0x200), &[2]).unwrap()),
+/// ];
+/// let b = [
+///     (ms(1), CanDataFrame::new(id(0x100), &[1]).unwrap()),
+///     (ms(11), CanDataFrame::new(id(0x200), &[3]).unwrap()),
+/// ];
+///
+/// let diffs = aligned_diff(&a, &b, ms(5)).unwrap();
+/// assert_eq!(diffs, [(Id::Standard(id(0x200)), ms(10))]);
+/// ```
+pub fn aligned_diff<F: Frame, G: Frame>(
+    a: &[(Duration, F)],
+    b: &[(Duration, G)],
+    window: Duration,
+) -> Result<Vec<(Id, Duration)>> {
+    let mut diffs: Vec<(Id, Duration)> = Vec::new();
+
+    let mut record = |id: Id, t: Duration| -> Result {
+        match diffs.iter_mut().find(|(x, _)| *x == id) {
+            Some((_, first)) if t < *first => *first = t,
+            Some(_) => (),
+            None => diffs.try_push((id, t))?,
+        }
+        Ok(())
+    };
+
+    for (t, frame) in a {
+        let id = frame.id();
+        match closest_match(b, id, *t, window) {
+            Some(other) if other.data() == frame.data() => (),
+            _ => record(id, *t)?,
+        }
+    }
+    for (t, frame) in b {
+        let id = frame.id();
+        match closest_match(a, id, *t, window) {
+            Some(other) if other.data() == frame.data() => (),
+            _ => record(id, *t)?,
+        }
+    }
+
+    diffs.sort_unstable_by_key(|(_, t)| *t);
+    Ok(diffs)
+}
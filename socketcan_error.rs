@@ -110,8 +110,6 @@ pub enum CanError {
     Unknown(u32),
 }
 
-impl crate::core_error::Error for CanError {}
-
 impl fmt::Display for CanError {
     fn fmt(&self, f: &mut  fmt::Formatter<'_>) -> fmt::Result {
         use CanError::*;
@@ -151,6 +149,43 @@ impl crate::socketcan_embedded::Error for CanError {
     }
 }
 
+impl From<CanError> for crate::error::Error {
+    /// Converts a CAN bus error into a kernel error code.
+    ///
+    /// This lets bus errors be returned through the normal error path for
+    /// callers that don't want to decode error frames themselves:
+    /// - `TransmitTimeout` => `ETIMEDOUT`
+    /// - `LostArbitration` => `EAGAIN`
+    /// - `NoAck` => `ECOMM`
+    /// - `BusOff` => `ENETDOWN`
+    /// - `TransceiverError` => `ENODEV`
+    /// - `Restarted` => `ENETRESET`
+    /// - Anything else => `EIO`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::error::{code::*, Error};
+    /// # use kernel::socketcan_error::CanError;
+    /// assert_eq!(Error::from(CanError::TransmitTimeout), ETIMEDOUT);
+    /// assert_eq!(Error::from(CanError::BusOff), ENETDOWN);
+    /// assert_eq!(Error::from(CanError::BusError), EIO);
+    /// ```
+    fn from(err: CanError) -> Self {
+        use crate::error::code::*;
+        use CanError::*;
+        match err {
+            TransmitTimeout => ETIMEDOUT,
+            LostArbitration(_) => EAGAIN,
+            NoAck => ECOMM,
+            BusOff => ENETDOWN,
+            TransceiverError => ENODEV,
+            Restarted => ENETRESET,
+            _ => EIO,
+        }
+    }
+}
+
 impl From<CanErrorFrame> for CanError {
     /// Constructs a CAN error from an error frame.
     fn from(frame: CanErrorFrame) -> Self {
@@ -184,6 +219,162 @@ impl From<CanErrorFrame> for CanError {
 
 
 
+// ===== BusStateTracker =====
+
+/// Controller problem error class, detailed in `data[1]`.
+const CAN_ERR_CRTL: u32 = 0x0004;
+/// Bus-off error class.
+const CAN_ERR_BUSOFF: u32 = 0x0040;
+/// Controller restarted error class.
+const CAN_ERR_RESTARTED: u32 = 0x0100;
+
+/// The error state of a CAN controller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BusState {
+    /// The controller takes part in bus communication normally.
+    ErrorActive,
+    /// The controller has seen enough errors that it may only signal them
+    /// passively.
+    ErrorPassive,
+    /// The controller has seen too many errors and is off the bus.
+    BusOff,
+}
+
+impl fmt::Display for BusState {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        use BusState::*;
+        let msg = match *self {
+            ErrorActive => "ERROR ACTIVE",
+            ErrorPassive => "ERROR PASSIVE",
+            BusOff => "BUS OFF",
+        };
+        write!(f, "{}", msg)
+    }
+}
+
+/// Tracks the error state of a CAN controller from its error frames.
+///
+/// Each received error frame is fed to [`BusStateTracker::update()`],
+/// which inspects the bus-off, restarted, and controller problem classes
+/// to follow the controller between the error-active, error-passive and
+/// bus-off states. The tracker starts in the error-active state.
+///
+/// Transitions are reported in two ways: `update()` returns the new state
+/// when it changes, and a callback registered with
+/// [`BusStateTracker::on_transition()`] is called with the old and new
+/// states.
+///
+/// # Examples
+///
+/// ```
+/// # use kernel::socketcan_error::{BusState, BusStateTracker, CanError, ControllerProblem};
+/// # use kernel::socketcan_frame::CanErrorFrame;
+/// let mut tracker = BusStateTracker::new();
+/// assert_eq!(tracker.state(), BusState::ErrorActive);
+///
+/// let passive = CanError::ControllerProblem(ControllerProblem::TransmitErrorPassive);
+/// let frame = CanErrorFrame::from(passive);
+/// assert_eq!(tracker.update(&frame), Some(BusState::ErrorPassive));
+///
+/// let bus_off = CanErrorFrame::from(CanError::BusOff);
+/// assert_eq!(tracker.update(&bus_off), Some(BusState::BusOff));
+/// assert_eq!(tracker.update(&bus_off), None);
+/// assert_eq!(tracker.state(), BusState::BusOff);
+///
+/// let restarted = CanErrorFrame::from(CanError::Restarted);
+/// assert_eq!(tracker.update(&restarted), Some(BusState::ErrorActive));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct BusStateTracker {
+    state: BusState,
+    callback: Option<fn(BusState, BusState)>,
+}
+
+impl Default for BusStateTracker {
+    fn default() -> Self {
+        Self {
+            state: BusState::ErrorActive,
+            callback: None,
+        }
+    }
+}
+
+impl BusStateTracker {
+    /// Creates a tracker, in the error-active state.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gets the current state of the controller.
+    pub fn state(&self) -> BusState {
+        self.state
+    }
+
+    /// Sets a callback to be called on each state transition.
+    ///
+    /// The callback gets the old state and then the new one. It is called
+    /// from `update()`, after the state has changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use core::sync::atomic::{AtomicBool, Ordering};
+    /// # use kernel::socketcan_error::{BusState, BusStateTracker, CanError};
+    /// # use kernel::socketcan_frame::CanErrorFrame;
+    /// static WENT_OFF: AtomicBool = AtomicBool::new(false);
+    ///
+    /// let mut tracker = BusStateTracker::new();
+    /// tracker.on_transition(|_old, new| {
+    ///     if new == BusState::BusOff {
+    ///         WENT_OFF.store(true, Ordering::Relaxed);
+    ///     }
+    /// });
+    ///
+    /// tracker.update(&CanErrorFrame::from(CanError::BusOff));
+    /// assert!(WENT_OFF.load(Ordering::Relaxed));
+    /// ```
+    pub fn on_transition(&mut self, callback: fn(BusState, BusState)) {
+        self.callback = Some(callback);
+    }
+
+    /// Updates the state from a received error frame.
+    ///
+    /// This returns the new state if the frame caused a transition, or
+    /// `None` if the state is unchanged.
+    pub fn update(&mut self, frame: &CanErrorFrame) -> Option<BusState> {
+        let bits = frame.error_bits();
+        let prob = frame.data()[1];
+
+        let next = if bits & CAN_ERR_BUSOFF != 0 {
+            BusState::BusOff
+        } else if bits & CAN_ERR_RESTARTED != 0 {
+            BusState::ErrorActive
+        } else if bits & CAN_ERR_CRTL != 0 {
+            let passive = ControllerProblem::ReceiveErrorPassive as u8
+                | ControllerProblem::TransmitErrorPassive as u8;
+            if prob & passive != 0 {
+                BusState::ErrorPassive
+            } else if prob & ControllerProblem::Active as u8 != 0 {
+                BusState::ErrorActive
+            } else {
+                self.state
+            }
+        } else {
+            self.state
+        };
+
+        if next != self.state {
+            let prev = core::mem::replace(&mut self.state, next);
+            if let Some(callback) = self.callback {
+                callback(prev, next);
+            }
+            Some(next)
+        } else {
+            None
+        }
+    }
+}
+
 // ===== ControllerProblem =====
 
 /// Error status of the CAN conroller.
@@ -485,9 +676,33 @@ pub enum ConstructionError {
     IDTooLarge,
     /// Larger payload reported than can be held in the frame.
     TooMuchData,
+    /// A value is out of the range that can be held in its field.
+    ValueOutOfRange,
 }
 
-//impl crate::socketcan_embedded::Error for ConstructionError {}
+impl crate::socketcan_embedded::Error for ConstructionError {
+    /// Maps the construction error to the closest generic error kind.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_embedded::{Error, ErrorKind};
+    /// # use kernel::socketcan_error::ConstructionError;
+    /// assert_eq!(ConstructionError::WrongFrameType.kind(), ErrorKind::FrameFormat);
+    /// assert_eq!(ConstructionError::IDTooLarge.kind(), ErrorKind::FrameFormat);
+    /// assert_eq!(ConstructionError::TooMuchData.kind(), ErrorKind::FrameFormat);
+    /// assert_eq!(ConstructionError::ValueOutOfRange.kind(), ErrorKind::Other);
+    /// ```
+    fn kind(&self) -> crate::socketcan_embedded::ErrorKind {
+        use ConstructionError::*;
+        match *self {
+            WrongFrameType | IDTooLarge | TooMuchData => {
+                crate::socketcan_embedded::ErrorKind::FrameFormat
+            }
+            ValueOutOfRange => crate::socketcan_embedded::ErrorKind::Other,
+        }
+    }
+}
 
 impl fmt::Display for ConstructionError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
@@ -496,6 +711,7 @@ impl fmt::Display for ConstructionError {
             WrongFrameType => "Incompatible frame type",
             IDTooLarge => "CAN ID too large",
             TooMuchData => "Payload is too large",
+            ValueOutOfRange => "Value is out of range",
         };
         write!(f, "{}", msg)
     }
@@ -0,0 +1,114 @@
+// Implements CAN ID acceptance filters.
+//
+// This file is part of the Rust 'socketcan-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+
+//! CAN ID filters.
+//!
+//! A filter matches a received frame when
+//!
+//! ```text
+//! <received_can_id> & mask == can_id & mask
+//! ```
+//!
+//! using the full SocketCAN ID word, including the EFF/RTR/ERR flags. This
+//! is the same matching the kernel applies for the `CAN_RAW_FILTER` socket
+//! option.
+
+use crate::error::{code::EINVAL, Result};
+use crate::socketcan_frame::*;
+use alloc::vec::Vec;
+
+/// A CAN ID filter, compatible with the C `can_filter`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[repr(C)]
+pub struct CanFilter {
+    /// The ID word to match against
+    pub can_id: canid_t,
+    /// The bits of the ID word that must match
+    pub can_mask: canid_t,
+}
+
+impl CanFilter {
+    /// Creates a filter with the specified ID word and mask.
+    pub fn new(can_id: canid_t, can_mask: canid_t) -> Self {
+        Self { can_id, can_mask }
+    }
+
+    /// Checks whether a received ID word passes the filter.
+    pub fn matches(&self, can_id: canid_t) -> bool {
+        can_id & self.can_mask == self.can_id & self.can_mask
+    }
+
+    /// Creates the minimal set of filters that match an inclusive range of
+    /// raw IDs.
+    ///
+    /// As with `id_from_raw()`, IDs <= 0x7FF are treated as standard IDs
+    /// and larger ones as extended IDs, and each filter also matches the
+    /// EFF flag so that only frames of the right format pass. The range is
+    /// split into blocks that are aligned to a power of two, each covered
+    /// by a single filter.
+    ///
+    /// This fails with `EINVAL` if `start` is after `end`, or if `end` is
+    /// not a valid extended ID.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_filter::CanFilter;
+    /// let passes = |filters: &[CanFilter], id| filters.iter().any(|f| f.matches(id));
+    ///
+    /// // An aligned block needs a single filter
+    /// let filters = CanFilter::from_range(0x100, 0x1FF).unwrap();
+    /// assert_eq!(filters.len(), 1);
+    ///
+    /// // An arbitrary range needs several
+    /// let filters = CanFilter::from_range(0x123, 0x456).unwrap();
+    /// assert!(filters.len() > 1);
+    /// for id in 0..=0x7FF {
+    ///     assert_eq!(passes(&filters, id), (0x123..=0x456).contains(&id));
+    /// }
+    /// ```
+    pub fn from_range(start: u32, end: u32) -> Result<Vec<CanFilter>> {
+        if start > end || end > _CAN_EFF_MASK {
+            return Err(EINVAL);
+        }
+
+        let mut filters = Vec::new();
+        if start <= _CAN_SFF_MASK {
+            let end = end.min(_CAN_SFF_MASK);
+            Self::push_blocks(&mut filters, start, end, 0, _CAN_SFF_MASK)?;
+        }
+        if end > _CAN_SFF_MASK {
+            let start = start.max(_CAN_SFF_MASK + 1);
+            Self::push_blocks(&mut filters, start, end, _CAN_EFF_FLAG, _CAN_EFF_MASK)?;
+        }
+        Ok(filters)
+    }
+
+    /// Covers the range with filters for aligned power-of-two blocks.
+    fn push_blocks(
+        filters: &mut Vec<CanFilter>,
+        start: u32,
+        end: u32,
+        flag: canid_t,
+        id_mask: canid_t,
+    ) -> Result {
+        let (mut lo, end) = (start as u64, end as u64);
+        while lo <= end {
+            // The largest block aligned at `lo` that doesn't pass `end`.
+            let mut size = if lo == 0 { 1u64 << 32 } else { 1u64 << lo.trailing_zeros() };
+            while lo + size - 1 > end {
+                size >>= 1;
+            }
+            let block_mask = !((size - 1) as u32) & id_mask;
+            filters.try_push(CanFilter::new(lo as u32 | flag, block_mask | _CAN_EFF_FLAG))?;
+            lo += size;
+        }
+        Ok(())
+    }
+}
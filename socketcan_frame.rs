@@ -35,7 +35,12 @@
 /// Different imports : 
 use crate::socketcan_id::*; 
 use crate::socketcan_embedded::*; 
+use crate::socketcan_embedded::Frame as _;
 use crate::socketcan_error::*; 
+#[cfg(feature = "canxl")]
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::hash::{Hash, Hasher};
 
 /// Constants To be rechecked with libc
 pub const _CANFD_BRS: u32 = 1;
@@ -48,6 +53,13 @@ pub const _CAN_MAX_DLEN: u32 = 8;
 pub const _CAN_SFF_MASK: u32 = 2047;
 pub const _CAN_ERR_MASK: u32 = 536870911;
 pub const _CAN_EFF_MASK: u32 = 536870911;
+pub const _CANXL_MIN_DLEN: u32 = 1;
+pub const _CANXL_MAX_DLEN: u32 = 2048;
+pub const _CANXL_XLF: u32 = 128;
+pub const _CANXL_SEC: u32 = 1;
+pub const _CANXL_PRIO_MASK: u32 = 2047;
+pub const _CANXL_VCID_OFFSET: u32 = 16;
+pub const _CANXL_VCID_MASK: u32 = 16711680;
 
 
 
@@ -72,6 +84,71 @@ impl IdFlags {
     pub fn is_error(&self) -> bool {
         (self.can_id & _CAN_ERR_FLAG) != 0
     }
+    /// Gets only the EFF/RTR/ERR flag bits of the ID word.
+    pub fn bits(&self) -> u32 {
+        self.can_id & (_CAN_EFF_FLAG | _CAN_RTR_FLAG | _CAN_ERR_FLAG)
+    }
+}
+
+/// Writes the names of the set flags, separated by `|`, or the raw
+/// value if none of them are set.
+fn fmt_flag_names(
+    f: &mut core::fmt::Formatter<'_>,
+    bits: u32,
+    names: &[(u32, &str)],
+) -> core::fmt::Result {
+    let mut first = true;
+    for &(flag, name) in names {
+        if bits & flag != 0 {
+            if !first {
+                f.write_str(" | ")?;
+            }
+            f.write_str(name)?;
+            first = false;
+        }
+    }
+    if first {
+        write!(f, "{:#x}", bits)?;
+    }
+    Ok(())
+}
+
+/// Shows the names of the set flags, like `IdFlags(EFF | RTR)`.
+///
+/// # Examples
+///
+/// ```
+/// # use kernel::socketcan_frame::*;
+/// let flags = IdFlags::new(0x100 | _CAN_EFF_FLAG | _CAN_RTR_FLAG);
+/// assert_eq!(format!("{:?}", flags), "IdFlags(EFF | RTR)");
+/// assert_eq!(format!("{:#b}", flags), "0b11000000000000000000000000000000");
+/// assert_eq!(format!("{:?}", IdFlags::new(0x100)), "IdFlags(0x0)");
+///
+/// let flags = FdFlags::new(_CANFD_BRS | _CANFD_ESI);
+/// assert_eq!(format!("{:?}", flags), "FdFlags(BRS | ESI)");
+/// assert_eq!(format!("{:b}", flags), "11");
+/// ```
+impl core::fmt::Debug for IdFlags {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("IdFlags(")?;
+        fmt_flag_names(
+            f,
+            self.bits(),
+            &[
+                (_CAN_EFF_FLAG, "EFF"),
+                (_CAN_RTR_FLAG, "RTR"),
+                (_CAN_ERR_FLAG, "ERR"),
+            ],
+        )?;
+        f.write_str(")")
+    }
+}
+
+/// Formats the raw flag bits, without the rest of the ID word.
+impl core::fmt::Binary for IdFlags {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Binary::fmt(&self.bits(), f)
+    }
 }
 
 
@@ -93,10 +170,27 @@ impl FdFlags {
     }
 }
 
+/// Shows the names of the set flags, like `FdFlags(BRS | ESI)`.
+impl core::fmt::Debug for FdFlags {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("FdFlags(")?;
+        fmt_flag_names(f, self.flags, &[(_CANFD_BRS, "BRS"), (_CANFD_ESI, "ESI")])?;
+        f.write_str(")")
+    }
+}
+
+/// Formats the raw flag bits.
+impl core::fmt::Binary for FdFlags {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Binary::fmt(&self.flags, f)
+    }
+}
+
 
 /// This struct defines some of the fields of the can_frame
 /// Could look at a potential bindgen use 
 #[repr(C, align(8))]
+#[derive(Clone, Copy)]
 pub struct can_frame {
     pub can_id: canid_t,
     pub can_dlc: u8,
@@ -108,6 +202,7 @@ pub type canid_t = u32;
 
 /// This struct defines some of the fields of the canfd_frame
 /// Could look at a potential bindgen use 
+#[derive(Clone, Copy)]
 pub struct canfd_frame {
     pub can_id: canid_t,
     pub len: u8,
@@ -116,6 +211,18 @@ pub struct canfd_frame {
     /* private fields */
 }
 
+/// This struct defines the fields of the canxl_frame
+#[cfg(feature = "canxl")]
+#[repr(C)]
+pub struct canxl_frame {
+    pub prio: canid_t,
+    pub flags: u8,
+    pub sdt: u8,
+    pub len: u16,
+    pub af: u32,
+    pub data: [u8; 2048],
+}
+
 /// An error mask that will cause SocketCAN to report all errors
 pub const _ERR_MASK_ALL: u32 = _CAN_ERR_MASK;
 /// An error mask that will cause SocketCAN to silently drop all errors
@@ -150,6 +257,185 @@ pub fn id_from_raw(id: u32) -> Option<Id> {
     Some(id)
 }
 
+impl Id {
+    /// Creates a CAN ID from a raw integer value, rejecting any flag bits.
+    ///
+    /// Like `id_from_raw()`, a value <= 0x7FF is taken as a standard ID and
+    /// anything larger as an extended ID. But this is an error if any of the
+    /// EFF/RTR/ERR flag bits are set, (i.e. the value is above 0x1FFF_FFFF)
+    /// so that flags can't be accidentally passed in as part of an ID.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_error::ConstructionError;
+    /// # use kernel::socketcan_frame::_CAN_EFF_FLAG;
+    /// # use kernel::socketcan_id::{ExtendedId, Id, StandardId};
+    /// assert_eq!(
+    ///     Id::from_raw_checked(0x100),
+    ///     Ok(Id::Standard(StandardId::new(0x100).unwrap()))
+    /// );
+    /// assert_eq!(
+    ///     Id::from_raw_checked(0x1234_5678),
+    ///     Ok(Id::Extended(ExtendedId::new(0x1234_5678).unwrap()))
+    /// );
+    /// assert_eq!(
+    ///     Id::from_raw_checked(0x100 | _CAN_EFF_FLAG),
+    ///     Err(ConstructionError::IDTooLarge)
+    /// );
+    /// ```
+    pub fn from_raw_checked(raw: u32) -> Result<Id, ConstructionError> {
+        if raw & (_CAN_EFF_FLAG | _CAN_RTR_FLAG | _CAN_ERR_FLAG) != 0 {
+            return Err(ConstructionError::IDTooLarge);
+        }
+        id_from_raw(raw).ok_or(ConstructionError::IDTooLarge)
+    }
+}
+
+impl TryFrom<u32> for Id {
+    type Error = ConstructionError;
+
+    /// Creates a CAN ID from a raw integer value.
+    /// See [`Id::from_raw_checked()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_error::ConstructionError;
+    /// # use kernel::socketcan_id::{ExtendedId, Id, StandardId};
+    /// let id = Id::try_from(0x1FFF_FFFFu32).unwrap();
+    /// assert_eq!(id, Id::Extended(ExtendedId::MAX));
+    /// assert_eq!(Id::try_from(0x2000_0000u32), Err(ConstructionError::IDTooLarge));
+    ///
+    /// assert_eq!(u16::from(StandardId::MAX), 0x7FF);
+    /// assert_eq!(u32::from(ExtendedId::MAX), 0x1FFF_FFFF);
+    /// ```
+    fn try_from(raw: u32) -> Result<Self, Self::Error> {
+        Id::from_raw_checked(raw)
+    }
+}
+
+impl TryFrom<u16> for StandardId {
+    type Error = ConstructionError;
+
+    /// Creates a standard CAN ID from a raw 16-bit value.
+    ///
+    /// This fails if the value is larger than 0x7FF.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_error::ConstructionError;
+    /// # use kernel::socketcan_id::StandardId;
+    /// assert_eq!(StandardId::try_from(0x7FFu16), Ok(StandardId::MAX));
+    /// assert_eq!(StandardId::try_from(0x800u16), Err(ConstructionError::IDTooLarge));
+    /// ```
+    fn try_from(raw: u16) -> Result<Self, Self::Error> {
+        StandardId::new(raw).ok_or(ConstructionError::IDTooLarge)
+    }
+}
+
+/// Gets the `Id` from a composite SocketCAN ID word.
+///
+/// This is the inverse of `id_to_canid_t()`. The EFF flag selects between
+/// a standard and an extended ID, and the other flags are discarded.
+pub fn id_from_canid_t(can_id: canid_t) -> Id {
+    if can_id & _CAN_EFF_FLAG != 0 {
+        // SAFETY: The value is masked to 29 bits.
+        unsafe { ExtendedId::new_unchecked(can_id & _CAN_EFF_MASK) }.into()
+    } else {
+        // SAFETY: The value is masked to 11 bits.
+        unsafe { StandardId::new_unchecked((can_id & _CAN_SFF_MASK) as u16) }.into()
+    }
+}
+
+/// Splits a composite SocketCAN ID word into the `Id` and its flags.
+///
+/// For data and remote frames, the EFF flag selects between an 11-bit
+/// standard and a 29-bit extended ID, as with `id_from_canid_t()`. For an
+/// error frame, the `Id` holds the error class bits, masked with
+/// `_CAN_ERR_MASK`, the same as the `id()` of a `CanErrorFrame`.
+///
+/// # Examples
+///
+/// ```
+/// # use kernel::socketcan_frame::*;
+/// # use kernel::socketcan_id::{ExtendedId, Id, StandardId};
+/// let (id, flags) = decode_id_word(0x123);
+/// assert_eq!(id, Id::Standard(StandardId::new(0x123).unwrap()));
+/// assert!(!flags.is_extended());
+///
+/// let (id, flags) = decode_id_word(0x1234_5678 | _CAN_EFF_FLAG);
+/// assert_eq!(id, Id::Extended(ExtendedId::new(0x1234_5678).unwrap()));
+/// assert!(flags.is_extended());
+///
+/// let (id, flags) = decode_id_word(0x123 | _CAN_RTR_FLAG);
+/// assert_eq!(id, Id::Standard(StandardId::new(0x123).unwrap()));
+/// assert!(flags.is_remote());
+///
+/// let (id, flags) = decode_id_word(0x0040 | _CAN_ERR_FLAG);
+/// assert_eq!(id, Id::Standard(StandardId::new(0x040).unwrap()));
+/// assert!(flags.is_error());
+/// ```
+pub fn decode_id_word(word: canid_t) -> (Id, IdFlags) {
+    let id = if word & _CAN_ERR_FLAG != 0 {
+        id_from_raw(word & _CAN_ERR_MASK).unwrap_or(Id::Standard(StandardId::ZERO))
+    } else {
+        id_from_canid_t(word)
+    };
+    (id, IdFlags::new(word))
+}
+
+/// Replaces the ID in a composite SocketCAN ID word.
+///
+/// The RTR and ERR flags of `word` are preserved, while the ID bits are
+/// rewritten and the EFF flag is set or cleared to match the new `id`.
+pub fn canid_t_with_id(word: canid_t, id: impl Into<Id>) -> canid_t {
+    (word & (_CAN_RTR_FLAG | _CAN_ERR_FLAG)) | id_to_canid_t(id)
+}
+
+/// Repairs swapped EFF/RTR flags in an ID word from a misconfigured source.
+///
+/// Some buggy tools set the RTR flag where they meant the EFF flag, or the
+/// other way around. Given the format the ID is expected to have, this
+/// swaps the two flags when only the wrong one is set:
+/// - If an extended ID is expected, an RTR flag without an EFF flag is
+///   taken to be the EFF flag.
+/// - If a standard ID is expected, an EFF flag without an RTR flag, on an
+///   ID that fits in 11 bits, is taken to be the RTR flag.
+///
+/// This is a best-effort fixup for importing data from sources that are
+/// known to be buggy. A genuine remote frame could be misread, so it should
+/// not be applied to data from well-behaved sources. Error frames are
+/// returned unchanged.
+///
+/// # Examples
+///
+/// ```
+/// # use kernel::socketcan_frame::{decode_id_word, repair_flags, _CAN_RTR_FLAG};
+/// # use kernel::socketcan_id::{ExtendedId, Id};
+/// // An extended ID, where the source set RTR instead of EFF
+/// let word = 0x1234 | _CAN_RTR_FLAG;
+///
+/// let (id, flags) = decode_id_word(repair_flags(word, true));
+/// assert_eq!(id, Id::Extended(ExtendedId::new(0x1234).unwrap()));
+/// assert!(flags.is_extended());
+/// assert!(!flags.is_remote());
+///
+/// // Nothing to repair if the expected format matches
+/// assert_eq!(repair_flags(word, false), word);
+/// ```
+pub fn repair_flags(word: canid_t, expect_extended: bool) -> canid_t {
+    let flags = word & (_CAN_EFF_FLAG | _CAN_RTR_FLAG | _CAN_ERR_FLAG);
+    if expect_extended && flags == _CAN_RTR_FLAG {
+        (word & !_CAN_RTR_FLAG) | _CAN_EFF_FLAG
+    } else if !expect_extended && flags == _CAN_EFF_FLAG && word & _CAN_EFF_MASK <= _CAN_SFF_MASK {
+        (word & !_CAN_EFF_FLAG) | _CAN_RTR_FLAG
+    } else {
+        word
+    }
+}
+
 // ===== can_frame =====
 
 /// Creates a default C `can_frame`.
@@ -166,6 +452,178 @@ pub fn canfd_frame_default() -> canfd_frame {
     unsafe {core::mem::zeroed() }
 }
 
+/// Creates a C `can_frame` from its raw bytes.
+///
+/// This is the reverse of `AsPtr::as_bytes()`, for frames read from a
+/// source other than a socket, such as a file or a USB bridge. The buffer
+/// must be exactly the size of a `can_frame`.
+///
+/// # Examples
+///
+/// ```
+/// # use kernel::socketcan_embedded::Frame;
+/// # use kernel::socketcan_error::ConstructionError;
+/// # use kernel::socketcan_frame::{can_frame_from_bytes, AsPtr, CanDataFrame};
+/// # use kernel::socketcan_id::StandardId;
+/// let id = StandardId::new(0x100).unwrap();
+/// let frame = CanDataFrame::new(id, &[1, 2, 3]).unwrap();
+///
+/// let raw = can_frame_from_bytes(frame.as_bytes()).unwrap();
+/// let copy = CanDataFrame::try_from(raw).unwrap();
+/// assert!(copy == frame);
+///
+/// let short = &frame.as_bytes()[1..];
+/// assert_eq!(can_frame_from_bytes(short).err(), Some(ConstructionError::WrongFrameType));
+/// ```
+pub fn can_frame_from_bytes(buf: &[u8]) -> Result<can_frame, ConstructionError> {
+    if buf.len() != core::mem::size_of::<can_frame>() {
+        return Err(ConstructionError::WrongFrameType);
+    }
+    let mut frame = can_frame_default();
+    // SAFETY: The lengths match, and any bit pattern is a valid can_frame.
+    unsafe {
+        core::ptr::copy_nonoverlapping(
+            buf.as_ptr(),
+            &mut frame as *mut _ as *mut u8,
+            buf.len(),
+        );
+    }
+    Ok(frame)
+}
+
+/// Creates a C `canfd_frame` from its raw bytes.
+///
+/// This is the reverse of `AsPtr::as_bytes()`, for frames read from a
+/// source other than a socket, such as a file or a USB bridge. The buffer
+/// must be exactly the size of a `canfd_frame`.
+pub fn canfd_frame_from_bytes(buf: &[u8]) -> Result<canfd_frame, ConstructionError> {
+    if buf.len() != core::mem::size_of::<canfd_frame>() {
+        return Err(ConstructionError::WrongFrameType);
+    }
+    let mut frame = canfd_frame_default();
+    // SAFETY: The lengths match, and any bit pattern is a valid canfd_frame.
+    unsafe {
+        core::ptr::copy_nonoverlapping(
+            buf.as_ptr(),
+            &mut frame as *mut _ as *mut u8,
+            buf.len(),
+        );
+    }
+    Ok(frame)
+}
+
+// ===== CAN FD lengths =====
+
+/// The data lengths for each CAN FD DLC value
+const FD_DLC_LEN: [u8; 16] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 12, 16, 20, 24, 32, 48, 64];
+
+/// Gets the CAN FD DLC that can hold a payload of the specified length.
+///
+/// Lengths of 0-8 map directly to the DLC. Larger lengths that can't be
+/// represented exactly are rounded up to the next valid size: 12, 16, 20,
+/// 24, 32, 48, or 64 bytes. This returns `None` if `len` is more than 64.
+///
+/// # Examples
+///
+/// ```
+/// # use kernel::socketcan_frame::{fd_dlc_to_len, fd_len_to_dlc};
+/// assert_eq!(fd_len_to_dlc(8), Some(8));
+/// assert_eq!(fd_len_to_dlc(9), Some(9));
+/// assert_eq!(fd_dlc_to_len(9), 12);
+/// assert_eq!(fd_len_to_dlc(12), Some(9));
+/// assert_eq!(fd_len_to_dlc(13), Some(10));
+/// assert_eq!(fd_len_to_dlc(33), Some(14));
+/// assert_eq!(fd_dlc_to_len(14), 48);
+/// assert_eq!(fd_len_to_dlc(64), Some(15));
+/// assert_eq!(fd_len_to_dlc(65), None);
+/// ```
+pub fn fd_len_to_dlc(len: usize) -> Option<u8> {
+    FD_DLC_LEN
+        .iter()
+        .position(|&n| len <= n as usize)
+        .map(|dlc| dlc as u8)
+}
+
+/// Gets the length of the payload for a CAN FD DLC.
+///
+/// Only the lower 4 bits of the DLC are used.
+pub fn fd_dlc_to_len(dlc: u8) -> usize {
+    FD_DLC_LEN[(dlc & 0x0F) as usize] as usize
+}
+
+// The capacity constants must agree with the DLC encodings.
+crate::static_assert!(CanDataFrame::MAX_DLEN == FD_DLC_LEN[8] as usize);
+crate::static_assert!(CanFdFrame::MAX_DLEN == FD_DLC_LEN[15] as usize);
+
+/// Gets the worst-case number of bits that a classic frame occupies on the
+/// bus.
+///
+/// This counts every field from the start of frame through the
+/// interframe space, plus the maximum number of stuff bits that could be
+/// inserted (one after every four bits, from the start of frame through
+/// the CRC). A standard frame with 8 data bytes is 135 bits, and an
+/// extended one is 160 bits.
+///
+/// # Examples
+///
+/// ```
+/// # use kernel::socketcan_frame::{classic_bit_length, CanDataFrame, Frame};
+/// assert_eq!(classic_bit_length(false, 8), 135);
+/// assert_eq!(classic_bit_length(true, 8), 160);
+/// assert_eq!(classic_bit_length(false, 0), 55);
+///
+/// let frame = CanDataFrame::new_extended(0x1234, &[0; 8]).unwrap();
+/// assert_eq!(frame.bit_length(), 160);
+/// ```
+pub fn classic_bit_length(extended: bool, len: usize) -> usize {
+    // SOF, ID, RTR, IDE, r0, DLC, CRC (+ SRR, ID ext, r1 if extended)
+    let stuffed = (if extended { 54 } else { 34 }) + 8 * len;
+    // CRC delim, ACK slot + delim, EOF, IFS
+    stuffed + (stuffed - 1) / 4 + 13
+}
+
+/// Gets the worst-case number of bits that an FD frame occupies on the
+/// bus, as `(nominal, data)`.
+///
+/// The `data` bits are those of the data phase, from the ESI bit through
+/// the CRC, which are sent at the data bit rate when `brs` is set. The
+/// `nominal` bits are sent at the arbitration bit rate. Without a bit rate
+/// switch, all of the bits are counted as nominal, and `data` is zero.
+///
+/// Like [`classic_bit_length()`], this assumes the maximum number of
+/// dynamic stuff bits, and also counts the fixed stuff bits in the CRC.
+///
+/// The two counts are kept apart rather than scaled by a bit rate ratio,
+/// so the caller can weigh each phase by the bit rates actually in use.
+///
+/// # Examples
+///
+/// ```
+/// # use kernel::socketcan_frame::fd_bit_length;
+/// assert_eq!(fd_bit_length(false, 64, true), (34, 678));
+/// assert_eq!(fd_bit_length(false, 64, false), (712, 0));
+/// ```
+pub fn fd_bit_length(extended: bool, len: usize, brs: bool) -> (usize, usize) {
+    // SOF, ID, RRS, IDE, FDF, res, BRS (+ SRR, ID ext if extended)
+    let arb = if extended { 36 } else { 17 };
+    let arb = arb + (arb - 1) / 4;
+
+    // ESI, DLC, data with dynamic stuffing
+    let data = 5 + 8 * len;
+    let data = data + (data - 1) / 4;
+    // Stuff count, CRC, and the fixed stuff bits
+    let data = data + if len > 16 { 4 + 21 + 7 } else { 4 + 17 + 6 };
+
+    // CRC delim, ACK slot + delim, EOF, IFS
+    let tail = 13;
+
+    if brs {
+        (arb + tail, data)
+    } else {
+        (arb + data + tail, 0)
+    }
+}
+
 // ===== AsPtr trait =====
 
 /// Trait to get a pointer to an inner type
@@ -229,10 +687,50 @@ pub trait Frame: crate::socketcan_embedded::Frame {
         Self::new_remote(id_from_raw(id)?, dlc)
     }
 
+    /// Creates a frame with a 29-bit extended ID.
+    ///
+    /// Unlike `from_raw_id()`, this always creates an Extended ID, even
+    /// when the `id` is <= 0x7FF. Returns `None` if the `id` is out of
+    /// range for an Extended ID, or the data can not fit the frame.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_embedded::Frame as _;
+    /// # use kernel::socketcan_frame::{CanDataFrame, Frame};
+    /// let frame = CanDataFrame::new_extended(0x100, &[1, 2]).unwrap();
+    /// assert!(frame.is_extended());
+    /// assert_eq!(frame.raw_id(), 0x100);
+    ///
+    /// // The permissive constructor makes the same value a standard ID
+    /// let frame = CanDataFrame::from_raw_id(0x100, &[1, 2]).unwrap();
+    /// assert!(!frame.is_extended());
+    /// ```
+    fn new_extended(id: u32, data: &[u8]) -> Option<Self> {
+        Self::new(ExtendedId::new(id)?, data)
+    }
+
+    /// Creates a frame with an 11-bit standard ID.
+    ///
+    /// Returns `None` if the `id` is out of range for a Standard ID, or
+    /// the data can not fit the frame.
+    fn new_standard(id: u16, data: &[u8]) -> Option<Self> {
+        Self::new(StandardId::new(id)?, data)
+    }
+
     /// Get the composite SocketCAN ID word, with EFF/RTR/ERR flags
     fn id_word(&self) -> canid_t;
 
     /// Return the actual raw CAN ID (without EFF/RTR/ERR flags)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_frame::{CanDataFrame, Frame, _CAN_EFF_FLAG};
+    /// let frame = CanDataFrame::new_extended(0x1234, &[1, 2]).unwrap();
+    /// assert_eq!(frame.id_word(), 0x1234 | _CAN_EFF_FLAG);
+    /// assert_eq!(frame.raw_id(), 0x1234);
+    /// ```
     fn raw_id(&self) -> canid_t {
         let mask = if self.is_extended() {
             _CAN_EFF_MASK
@@ -260,6 +758,97 @@ pub trait Frame: crate::socketcan_embedded::Frame {
     /// Sets the CAN ID for the frame
     fn set_id(&mut self, id: impl Into<Id>);
 
+    /// Extracts a little endian 16-bit value from the data, starting at
+    /// the specified byte.
+    ///
+    /// This returns `None` if the value extends past the data length.
+    fn extract_u16_le(&self, start_byte: usize) -> Option<u16> {
+        let bytes = self.data().get(start_byte..start_byte + 2)?;
+        Some(u16::from_le_bytes(bytes.try_into().ok()?))
+    }
+
+    /// Extracts a big endian 16-bit value from the data, starting at the
+    /// specified byte.
+    ///
+    /// This returns `None` if the value extends past the data length.
+    fn extract_u16_be(&self, start_byte: usize) -> Option<u16> {
+        let bytes = self.data().get(start_byte..start_byte + 2)?;
+        Some(u16::from_be_bytes(bytes.try_into().ok()?))
+    }
+
+    /// Extracts a little endian 32-bit value from the data, starting at
+    /// the specified byte.
+    ///
+    /// This returns `None` if the value extends past the data length.
+    fn extract_u32_le(&self, start_byte: usize) -> Option<u32> {
+        let bytes = self.data().get(start_byte..start_byte + 4)?;
+        Some(u32::from_le_bytes(bytes.try_into().ok()?))
+    }
+
+    /// Extracts a big endian 32-bit value from the data, starting at the
+    /// specified byte.
+    ///
+    /// This returns `None` if the value extends past the data length.
+    fn extract_u32_be(&self, start_byte: usize) -> Option<u32> {
+        let bytes = self.data().get(start_byte..start_byte + 4)?;
+        Some(u32::from_be_bytes(bytes.try_into().ok()?))
+    }
+
+    /// Extracts an unsigned bit field of up to 64 bits from the data.
+    ///
+    /// The start bit uses the DBC numbering for the byte order, as
+    /// described in [`crate::socketcan_signal`]. This returns `None` if the
+    /// field extends past the data length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_frame::{CanDataFrame, Frame};
+    /// let frame = CanDataFrame::new_standard(0x100, &[0x34, 0x12, 0xAB]).unwrap();
+    ///
+    /// assert_eq!(frame.extract_u16_le(0), Some(0x1234));
+    /// assert_eq!(frame.extract_u16_be(0), Some(0x3412));
+    /// assert_eq!(frame.extract_bits(0, 16, true), Some(0x1234));
+    /// assert_eq!(frame.extract_bits(7, 16, false), Some(0x3412));
+    /// assert_eq!(frame.extract_bits(4, 4, true), Some(0x3));
+    ///
+    /// // Past the data length
+    /// assert_eq!(frame.extract_u16_le(2), None);
+    /// assert_eq!(frame.extract_u32_be(0), None);
+    /// assert_eq!(frame.extract_bits(16, 16, true), None);
+    /// ```
+    fn extract_bits(&self, start_bit: usize, len: usize, little_endian: bool) -> Option<u64> {
+        crate::socketcan_signal::extract_bits(self.data(), start_bit, len, little_endian)
+    }
+
+    /// Compares the bus priority of this frame with another.
+    ///
+    /// This models bus arbitration, so the frame that would win is ordered
+    /// first (`Less`). Frames are ordered by ID using the `Ord` of `Id`,
+    /// so a lower ID wins and a standard ID wins over an extended ID with
+    /// the same base. At the same ID, a data frame wins over a remote frame
+    /// since its RTR bit is dominant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use core::cmp::Ordering;
+    /// # use kernel::socketcan_embedded::Frame as _;
+    /// # use kernel::socketcan_frame::{CanDataFrame, CanRemoteFrame, Frame};
+    /// let standard = CanDataFrame::new_standard(0x100, &[]).unwrap();
+    /// let extended = CanDataFrame::new_extended(0x100, &[]).unwrap();
+    /// assert_eq!(standard.priority_cmp(&extended), Ordering::Less);
+    /// assert_eq!(extended.priority_cmp(&standard), Ordering::Greater);
+    ///
+    /// // At the same ID, the data frame wins over the remote frame
+    /// let remote = CanRemoteFrame::remote_from_raw_id(0x100, 0).unwrap();
+    /// assert_eq!(standard.priority_cmp(&remote), Ordering::Less);
+    /// ```
+    fn priority_cmp(&self, other: &impl crate::socketcan_embedded::Frame) -> core::cmp::Ordering {
+        self.id()
+            .cmp(&other.id())
+            .then(self.is_remote_frame().cmp(&other.is_remote_frame()))
+    }
 }
 
 
@@ -267,6 +856,7 @@ pub trait Frame: crate::socketcan_embedded::Frame {
 
 /// An FD socket can read a raw classic 2.0 or FD frame.
 #[allow(missing_debug_implementations)]
+#[derive(Clone, Copy)]
 pub enum CanRawFrame {
     /// A classic CAN 2.0 frame, with up to 8-bytes of data
     Classic(can_frame),
@@ -286,208 +876,2497 @@ impl From<canfd_frame> for CanRawFrame {
     }
 }
 
-// ===== CanErrorFrame =====
+impl CanRawFrame {
+    /// Converts the raw frame into a typed frame.
+    ///
+    /// A classic frame becomes a data, remote, or error frame, depending
+    /// on the flags in its ID word. An FD frame becomes a `CanFdFrame`.
+    /// This fails if the raw frame is not valid for its type, including an
+    /// FD frame with the RTR or ERR flag, which FD does not support.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_frame::*;
+    /// let mut raw = can_frame_default();
+    /// raw.can_id = 0x100;
+    /// let data = CanRawFrame::from(raw).into_frame();
+    /// assert!(matches!(data, Ok(CanFrame::Data(_))));
+    ///
+    /// raw.can_id = 0x100 | _CAN_RTR_FLAG;
+    /// let remote = CanRawFrame::from(raw).into_frame();
+    /// assert!(matches!(remote, Ok(CanFrame::Remote(_))));
+    ///
+    /// raw.can_id = 0x040 | _CAN_ERR_FLAG;
+    /// raw.can_dlc = 8;
+    /// let error = CanRawFrame::from(raw).into_frame();
+    /// assert!(matches!(error, Ok(CanFrame::Error(_))));
+    ///
+    /// let mut raw = canfd_frame_default();
+    /// raw.can_id = 0x100;
+    /// raw.len = 12;
+    /// let fd = CanRawFrame::from(raw).into_frame();
+    /// assert!(matches!(fd, Ok(CanFrame::Fd(_))));
+    ///
+    /// raw.can_id = 0x100 | _CAN_RTR_FLAG;
+    /// assert!(CanRawFrame::from(raw).into_frame().is_err());
+    /// ```
+    pub fn into_frame(self) -> Result<CanFrame, ConstructionError> {
+        match self {
+            Self::Classic(frame) => CanFrame::try_from(frame),
+            Self::Fd(frame) => CanFdFrame::try_from(frame).map(CanFrame::Fd),
+        }
+    }
+}
 
-/// A SocketCAN error frame.
-///
-/// This is returned from a read/receive by the OS or interface device
-/// driver when it detects an error, such as a problem on the bus. The
-/// frame encodes detailed information about the error, which can be
-/// managed directly by the application or converted into a Rust error
-///
-/// This is highly compatible with the `can_frame` from libc.
-/// ([ref](https://docs.rs/libc/latest/libc/struct.can_frame.html))
-pub struct CanErrorFrame(can_frame);
+/// Any frame, typed by its kind.
+#[allow(missing_debug_implementations)]
+#[derive(Clone, Copy)]
+pub enum CanAnyFrame {
+    /// A classic CAN 2.0 data frame
+    Normal(CanDataFrame),
+    /// A remote transmission request frame
+    Remote(CanRemoteFrame),
+    /// An error frame
+    Error(CanErrorFrame),
+    /// A flexible data rate frame
+    Fd(CanFdFrame),
+}
 
-impl CanErrorFrame {
-    /// Creates a CAN error frame from raw parts.
+impl CanAnyFrame {
+    /// Creates a frame directly from a composite SocketCAN ID word and data.
     ///
-    /// Note that an application would not normally _ever_ create an error
-    /// frame. This is included mainly to aid in implementing mocks and other
-    /// tests for an application.
+    /// The EFF/RTR/ERR flags in `word` select the type of frame:
+    /// - `_CAN_ERR_FLAG` creates an error frame
+    /// - `_CAN_RTR_FLAG` creates a remote frame, requesting `data.len()`
+    ///   bytes. The contents of `data` are not used.
+    /// - Otherwise a data frame is created.
     ///
-    /// The data byte slice should have the necessary codes for the supplied
-    /// error. They will be zero padded to a full frame of 8 bytes.
+    /// This fails if the data is longer than a classic frame can hold.
     ///
-    /// Also note:
-    /// - The error flag is forced on
-    /// - The other, non-error, flags are forced off
-    /// - The frame data is always padded with zero's to 8 bytes,
-    /// regardless of the length of the `data` parameter provided.
-    pub fn new_error(can_id: canid_t, data: &[u8]) -> Result<Self, ConstructionError> {
-        match data.len() {
-            n if n <= _CAN_MAX_DLEN as usize => {
-                let mut frame = can_frame_default();
-                frame.can_id = (can_id & _CAN_ERR_MASK) | _CAN_ERR_FLAG;
-                frame.can_dlc = _CAN_MAX_DLEN as u8;
-                frame.data[..n].copy_from_slice(data);
-                Ok(Self(frame))
-            }
-            _ => Err(ConstructionError::TooMuchData),
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_embedded::Frame;
+    /// # use kernel::socketcan_frame::*;
+    /// let word = 0x123 | _CAN_RTR_FLAG;
+    /// match CanAnyFrame::from_id_word(word, &[0; 4]).unwrap() {
+    ///     CanAnyFrame::Remote(frame) => {
+    ///         assert_eq!(frame.requested_dlc(), 4);
+    ///         assert!(frame.data().is_empty());
+    ///     }
+    ///     _ => panic!("expected a remote frame"),
+    /// }
+    ///
+    /// assert!(matches!(
+    ///     CanAnyFrame::from_id_word(0x123, &[1, 2]),
+    ///     Ok(CanAnyFrame::Normal(_))
+    /// ));
+    /// assert!(CanAnyFrame::from_id_word(0x123, &[0; 9]).is_err());
+    /// ```
+    pub fn from_id_word(word: canid_t, data: &[u8]) -> Result<Self, ConstructionError> {
+        if word & _CAN_ERR_FLAG != 0 {
+            CanErrorFrame::new_error(word, data).map(Self::Error)
+        } else if word & _CAN_RTR_FLAG != 0 {
+            CanRemoteFrame::init(word, data.len()).map(Self::Remote)
+        } else {
+            CanDataFrame::init(word, data).map(Self::Normal)
         }
     }
 
-    /// Return the error bits from the ID word of the error frame.
-    pub fn error_bits(&self) -> u32 {
-        self.id_word() & _CAN_ERR_MASK
+    /// Creates a frame from any type implementing the embedded `Frame` trait.
+    ///
+    /// This bridges frames from other drivers into this crate. A remote
+    /// frame is created if the source is a remote frame, otherwise a data
+    /// frame. The ID and data (or requested length) are copied over.
+    ///
+    /// This fails if the source frame reports more than 8 bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_embedded::Frame;
+    /// # use kernel::socketcan_frame::CanAnyFrame;
+    /// # use kernel::socketcan_id::{Id, StandardId};
+    /// // A remote frame from some other driver
+    /// struct MockFrame(Id, usize);
+    ///
+    /// impl Frame for MockFrame {
+    ///     fn new(_id: impl Into<Id>, _data: &[u8]) -> Option<Self> { None }
+    ///     fn new_remote(id: impl Into<Id>, dlc: usize) -> Option<Self> {
+    ///         Some(Self(id.into(), dlc))
+    ///     }
+    ///     fn is_extended(&self) -> bool { matches!(self.0, Id::Extended(_)) }
+    ///     fn is_remote_frame(&self) -> bool { true }
+    ///     fn id(&self) -> Id { self.0 }
+    ///     fn dlc(&self) -> usize { self.1 }
+    ///     fn data(&self) -> &[u8] { &[] }
+    /// }
+    ///
+    /// let id = StandardId::new(0x100).unwrap();
+    /// let mock = MockFrame::new_remote(id, 4).unwrap();
+    ///
+    /// match CanAnyFrame::from_embedded_any(&mock) {
+    ///     Ok(CanAnyFrame::Remote(frame)) => {
+    ///         assert_eq!(frame.id(), Id::Standard(id));
+    ///         assert_eq!(frame.dlc(), 4);
+    ///     }
+    ///     _ => panic!("expected a remote frame"),
+    /// }
+    /// ```
+    pub fn from_embedded_any<F>(frame: &F) -> Result<Self, ConstructionError>
+    where
+        F: crate::socketcan_embedded::Frame,
+    {
+        let can_id = id_to_canid_t(frame.id());
+        if frame.is_remote_frame() {
+            CanRemoteFrame::init(can_id, frame.dlc()).map(Self::Remote)
+        } else {
+            CanDataFrame::init(can_id, frame.data()).map(Self::Normal)
+        }
     }
+}
 
-    /// Converts this error frame into a `CanError`
-    pub fn into_error(self) -> CanError {
-        CanError::from(self)
+impl From<CanDataFrame> for CanAnyFrame {
+    fn from(frame: CanDataFrame) -> Self {
+        Self::Normal(frame)
     }
 }
 
-impl AsPtr for CanErrorFrame {
-    type Inner = can_frame;
+impl From<CanRemoteFrame> for CanAnyFrame {
+    fn from(frame: CanRemoteFrame) -> Self {
+        Self::Remote(frame)
+    }
+}
 
-    /// Gets a pointer to the CAN frame structure that is compatible with
-    /// the Linux C API.
-    fn as_ptr(&self) -> *const Self::Inner {
-        &self.0
+impl From<CanErrorFrame> for CanAnyFrame {
+    fn from(frame: CanErrorFrame) -> Self {
+        Self::Error(frame)
     }
+}
 
-    /// Gets a mutable pointer to the CAN frame structure that is compatible
-    /// with the Linux C API.
-    fn as_mut_ptr(&mut self) -> *mut Self::Inner {
-        &mut self.0
+impl From<CanFdFrame> for CanAnyFrame {
+    fn from(frame: CanFdFrame) -> Self {
+        Self::Fd(frame)
     }
 }
 
-impl crate::socketcan_embedded::Frame for CanErrorFrame {
-    /// Create an error frame.
-    ///
-    /// Note that an application would not normally _ever_ create an error
-    /// frame. This is included mainly to aid in implementing mocks and other
-    /// tests for an application.
+impl TryFrom<can_frame> for CanAnyFrame {
+    type Error = ConstructionError;
+
+    /// Try to create a typed frame from a C `can_frame`, using the flags
+    /// in the ID word to determine the type.
     ///
-    /// This will set the error bit in the CAN ID word.
-    fn new(id: impl Into<Id>, data: &[u8]) -> Option<Self> {
-        let can_id = id_to_canid_t(id);
-        Self::new_error(can_id, data).ok()
+    /// This dispatches through `CanFrame`.
+    fn try_from(frame: can_frame) -> Result<Self, ConstructionError> {
+        CanFrame::try_from(frame).map(Self::from)
     }
-    ///doc for something
-    fn id(&self) -> Id {
-        todo!() ; 
+}
+
+// ===== CanFrame =====
+
+/// A CAN frame of any kind.
+///
+/// This is what is read from a socket, and lets the application `match`
+/// on the kind of frame that was received. A classic (non-FD) socket only
+/// receives the data, remote, and error frames.
+#[allow(missing_debug_implementations)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CanFrame {
+    /// A data frame
+    Data(CanDataFrame),
+    /// A remote request frame
+    Remote(CanRemoteFrame),
+    /// An error frame
+    Error(CanErrorFrame),
+    /// A flexible data rate frame
+    Fd(CanFdFrame),
+}
+
+impl crate::socketcan_embedded::Frame for CanFrame {
+    /// Create a new data frame
+    fn new(id: impl Into<Id>, data: &[u8]) -> Option<Self> {
+        CanDataFrame::new(id, data).map(CanFrame::Data)
     }
-    /// The application should not create an error frame.
-    /// This will always return None.
-    fn new_remote(_id: impl Into<Id>, _dlc: usize) -> Option<Self> {
-        None
+
+    /// Create a new remote transmission request frame.
+    fn new_remote(id: impl Into<Id>, dlc: usize) -> Option<Self> {
+        CanRemoteFrame::new_remote(id, dlc).map(CanFrame::Remote)
     }
 
     /// Check if frame uses 29-bit extended ID format.
     fn is_extended(&self) -> bool {
-        self.is_extended() 
+        use CanFrame::*;
+        match self {
+            Data(frame) => frame.is_extended(),
+            Remote(frame) => frame.is_extended(),
+            Error(frame) => frame.is_extended(),
+            Fd(frame) => frame.is_extended(),
+        }
     }
 
     /// Check if frame is a remote transmission request.
     fn is_remote_frame(&self) -> bool {
-        false
+        matches!(self, CanFrame::Remote(_))
     }
 
     /// Check if frame is a data frame.
     fn is_data_frame(&self) -> bool {
-        false
+        matches!(self, CanFrame::Data(_) | CanFrame::Fd(_))
+    }
+
+    /// Return the frame identifier.
+    fn id(&self) -> Id {
+        use CanFrame::*;
+        match self {
+            Data(frame) => frame.id(),
+            Remote(frame) => frame.id(),
+            Error(frame) => frame.id(),
+            Fd(frame) => frame.id(),
+        }
     }
 
     /// Data length code
     fn dlc(&self) -> usize {
-        self.0.can_dlc as usize
+        use CanFrame::*;
+        match self {
+            Data(frame) => frame.dlc(),
+            Remote(frame) => frame.dlc(),
+            Error(frame) => frame.dlc(),
+            Fd(frame) => frame.dlc(),
+        }
     }
 
     /// A slice into the actual data.
-    /// An error frame can always acess the full 8-byte data payload.
     fn data(&self) -> &[u8] {
-        &self.0.data[..]
+        use CanFrame::*;
+        match self {
+            Data(frame) => frame.data(),
+            Remote(frame) => frame.data(),
+            Error(frame) => frame.data(),
+            Fd(frame) => frame.data(),
+        }
     }
 }
 
-impl CanErrorFrame {
+impl Frame for CanFrame {
     /// Get the composite SocketCAN ID word, with EFF/RTR/ERR flags
     fn id_word(&self) -> canid_t {
-        self.0.can_id
+        use CanFrame::*;
+        match self {
+            Data(frame) => frame.id_word(),
+            Remote(frame) => frame.id_word(),
+            Error(frame) => frame.id_word(),
+            Fd(frame) => frame.id_word(),
+        }
+    }
+
+    /// Return the actual raw CAN ID (without EFF/RTR/ERR flags)
+    fn raw_id(&self) -> canid_t {
+        use CanFrame::*;
+        match self {
+            Data(frame) => frame.raw_id(),
+            Remote(frame) => frame.raw_id(),
+            Error(frame) => frame.raw_id(),
+            Fd(frame) => frame.raw_id(),
+        }
     }
 
     /// Sets the CAN ID for the frame
-    /// This does nothing on an error frame.
-    fn set_id(&mut self, _id: impl Into<Id>) {}
+    fn set_id(&mut self, id: impl Into<Id>) {
+        use CanFrame::*;
+        match self {
+            Data(frame) => frame.set_id(id),
+            Remote(frame) => frame.set_id(id),
+            Error(frame) => frame.set_id(id),
+            Fd(frame) => frame.set_id(id),
+        }
+    }
 
-    /// Sets the data payload of the frame.
-    /// This is an error on an error frame.
-    fn set_data(&mut self, _data: &[u8]) -> Result<(), ConstructionError> {
-        Err(ConstructionError::WrongFrameType)
+    /// Get the data length
+    fn len(&self) -> usize {
+        use CanFrame::*;
+        match self {
+            Data(frame) => frame.len(),
+            Remote(frame) => frame.len(),
+            Error(frame) => frame.len(),
+            Fd(frame) => frame.len(),
+        }
     }
-    fn data(&self) -> &[u8] {
-        &self.0.data[..]
+}
+
+impl Ord for CanFrame {
+    /// Orders frames by bus priority, the highest priority first.
+    ///
+    /// Error frames come before all other frames, since they override any
+    /// transmission on the bus. Data and remote frames are ordered as by
+    /// `priority_cmp()`: by ID, then a data frame before a remote frame.
+    /// At the same priority, a classic frame comes before an FD frame, as
+    /// it would win arbitration with its dominant r0 bit against the
+    /// recessive FDF bit. Frames with the same priority are ordered by their
+    /// contents, so that the ordering is consistent with `Eq`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_error::CanError;
+    /// # use kernel::socketcan_frame::*;
+    /// let mut frames: Vec<CanFrame> = vec![
+    ///     CanDataFrame::new_extended(0x100, &[]).unwrap().into(),
+    ///     CanRemoteFrame::remote_from_raw_id(0x100, 0).unwrap().into(),
+    ///     CanDataFrame::new_standard(0x100, &[]).unwrap().into(),
+    ///     CanErrorFrame::from(CanError::BusOff).into(),
+    ///     CanDataFrame::new_standard(0x050, &[]).unwrap().into(),
+    /// ];
+    /// frames.sort();
+    ///
+    /// let words: Vec<canid_t> = frames.iter().map(|f| f.id_word()).collect();
+    /// assert!(matches!(frames[0], CanFrame::Error(_)));
+    /// assert_eq!(&words[1..], &[
+    ///     0x050,
+    ///     0x100,
+    ///     0x100 | _CAN_RTR_FLAG,
+    ///     0x100 | _CAN_EFF_FLAG,
+    /// ]);
+    /// ```
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        use core::cmp::Ordering::*;
+        use CanFrame::*;
+        match (self, other) {
+            (Error(a), Error(b)) => (a.0.can_id, a.0.can_dlc, a.0.data)
+                .cmp(&(b.0.can_id, b.0.can_dlc, b.0.data)),
+            (Error(_), _) => Less,
+            (_, Error(_)) => Greater,
+            _ => self.priority_cmp(other).then_with(|| match (self, other) {
+                (Data(a), Data(b)) => a
+                    .0
+                    .can_id
+                    .cmp(&b.0.can_id)
+                    .then_with(|| a.data().cmp(b.data())),
+                (Remote(a), Remote(b)) => (a.0.can_id, a.0.can_dlc)
+                    .cmp(&(b.0.can_id, b.0.can_dlc)),
+                (Fd(a), Fd(b)) => (a.0.can_id, a.0.flags)
+                    .cmp(&(b.0.can_id, b.0.flags))
+                    .then_with(|| a.data().cmp(b.data())),
+                (Fd(_), _) => Greater,
+                (_, Fd(_)) => Less,
+                _ => Equal,
+            }),
+        }
     }
 }
-/* 
-impl core::fmt::Debug for CanErrorFrame {
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "CanErrorFrame {{ ")?;
-        core::fmt::UpperHex::fmt(self, f)?;
-        write!(f, " }}")
+
+impl PartialOrd for CanFrame {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
     }
-}*/
-/* 
-impl core::fmt::UpperHex for CanErrorFrame {
-    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
-        write!(f, "{:X}#", self.0.can_id)?;
-        let mut parts = self.data().iter().map(|v| alloc::format!("{:02X}", v));
-        write!(f, "{}", parts.join(" "))
+}
+
+impl From<CanDataFrame> for CanFrame {
+    fn from(frame: CanDataFrame) -> Self {
+        Self::Data(frame)
     }
 }
-*/
-impl TryFrom<can_frame> for CanErrorFrame {
+
+impl From<CanRemoteFrame> for CanFrame {
+    fn from(frame: CanRemoteFrame) -> Self {
+        Self::Remote(frame)
+    }
+}
+
+impl From<CanErrorFrame> for CanFrame {
+    fn from(frame: CanErrorFrame) -> Self {
+        Self::Error(frame)
+    }
+}
+
+impl From<CanFdFrame> for CanFrame {
+    fn from(frame: CanFdFrame) -> Self {
+        Self::Fd(frame)
+    }
+}
+
+impl From<CanFrame> for CanAnyFrame {
+    fn from(frame: CanFrame) -> Self {
+        match frame {
+            CanFrame::Data(frame) => Self::Normal(frame),
+            CanFrame::Remote(frame) => Self::Remote(frame),
+            CanFrame::Error(frame) => Self::Error(frame),
+            CanFrame::Fd(frame) => Self::Fd(frame),
+        }
+    }
+}
+
+impl TryFrom<can_frame> for CanFrame {
     type Error = ConstructionError;
 
-    /// Try to create a `CanErrorFrame` from a C `can_frame`
+    /// Try to create a frame from a C `can_frame`, using the ERR and RTR
+    /// flags in the ID word to determine the kind of frame.
     ///
-    /// This will only succeed the C frame is marked as an error frame.
-    fn try_from(frame: can_frame) -> Result<Self, Self::Error> {
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_embedded::Frame;
+    /// # use kernel::socketcan_frame::*;
+    /// # use kernel::socketcan_id::StandardId;
+    /// let id = StandardId::new(0x123).unwrap();
+    /// let remote = CanRemoteFrame::new_remote(id, 2).unwrap();
+    /// let raw: can_frame = *remote.as_ref();
+    /// assert!(matches!(CanFrame::try_from(raw), Ok(CanFrame::Remote(_))));
+    ///
+    /// let data = CanDataFrame::new(id, &[1, 2]).unwrap();
+    /// let raw: can_frame = *data.as_ref();
+    /// assert!(matches!(CanFrame::try_from(raw), Ok(CanFrame::Data(_))));
+    /// ```
+    fn try_from(frame: can_frame) -> Result<Self, ConstructionError> {
         if frame.can_id & _CAN_ERR_FLAG != 0 {
-            Ok(Self(frame))
+            CanErrorFrame::try_from(frame).map(Self::Error)
+        } else if frame.can_id & _CAN_RTR_FLAG != 0 {
+            CanRemoteFrame::try_from(frame).map(Self::Remote)
         } else {
-            Err(ConstructionError::WrongFrameType)
+            CanDataFrame::try_from(frame).map(Self::Data)
         }
     }
 }
 
-impl From<CanError> for CanErrorFrame {
-    fn from(err: CanError) -> Self {
-        use CanError::*;
+// ===== CanDataFrame =====
 
-        let mut data = [0u8; _CAN_MAX_DLEN as usize ];
-        let id: canid_t = match err {
-            TransmitTimeout => 0x0001,
-            LostArbitration(bit) => {
-                data[0] = bit;
-                0x0002
-            }
-            ControllerProblem(prob) => {
-                data[1] = prob as u8;
-                0x0004
+/// The classic CAN 2.0 data frame with up to 8-bytes of data.
+///
+/// This is highly compatible with the `can_frame` from libc.
+/// ([ref](https://docs.rs/libc/latest/libc/struct.can_frame.html))
+#[derive(Clone, Copy)]
+pub struct CanDataFrame(can_frame);
+
+impl CanDataFrame {
+    /// The maximum data length code of a classic frame.
+    pub const MAX_DLC: usize = _CAN_MAX_DLEN as usize;
+
+    /// The maximum length of the data in a classic frame, in bytes.
+    pub const MAX_DLEN: usize = _CAN_MAX_DLEN as usize;
+
+    /// Initializes a CAN data frame from raw parts.
+    ///
+    /// The ID word is used as-is, so it should not have the RTR or ERR
+    /// flags set.
+    pub fn init(can_id: canid_t, data: &[u8]) -> Result<Self, ConstructionError> {
+        match data.len() {
+            n if n <= _CAN_MAX_DLEN as usize => {
+                let mut frame = can_frame_default();
+                frame.can_id = can_id;
+                frame.can_dlc = n as u8;
+                frame.data[..n].copy_from_slice(data);
+                Ok(Self(frame))
             }
-            ProtocolViolation { vtype, location } => {
-                data[2] = vtype as u8;
-                data[3] = location as u8;
-                0x0008
+            _ => Err(ConstructionError::TooMuchData),
+        }
+    }
+
+    /// Creates a standard data frame in a `const` context.
+    ///
+    /// This allows frames to be declared in `const` or `static` tables.
+    /// The length of the payload is fixed by the array, and using an array
+    /// longer than 8 bytes fails at compile time when used in a `const`
+    /// context, or panics otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_embedded::Frame;
+    /// # use kernel::socketcan_frame::CanDataFrame;
+    /// # use kernel::socketcan_id::StandardId;
+    /// const fn id(raw: u16) -> StandardId {
+    ///     match StandardId::new(raw) {
+    ///         Some(id) => id,
+    ///         None => panic!("invalid standard ID"),
+    ///     }
+    /// }
+    ///
+    /// static FRAMES: [CanDataFrame; 2] = [
+    ///     CanDataFrame::new_const(id(0x100), &[1, 2, 3]),
+    ///     CanDataFrame::new_const(id(0x200), &[]),
+    /// ];
+    ///
+    /// assert_eq!(FRAMES[0].id(), id(0x100).into());
+    /// assert_eq!(FRAMES[0].data(), &[1, 2, 3]);
+    /// assert_eq!(FRAMES[1].dlc(), 0);
+    /// ```
+    pub const fn new_const<const N: usize>(id: StandardId, data: &[u8; N]) -> Self {
+        assert!(N <= _CAN_MAX_DLEN as usize, "CAN data frame payload is too large");
+
+        let mut buf = [0u8; _CAN_MAX_DLEN as usize];
+        let mut i = 0;
+        while i < N {
+            buf[i] = data[i];
+            i += 1;
+        }
+        Self(can_frame {
+            can_id: id.as_raw() as canid_t,
+            can_dlc: N as u8,
+            data: buf,
+        })
+    }
+
+    /// Sets the data payload of the frame.
+    ///
+    /// This updates the data length code to match, and zero-pads the rest
+    /// of the 8-byte buffer. It fails, leaving the frame unchanged, if the
+    /// data is longer than 8 bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_embedded::Frame;
+    /// # use kernel::socketcan_error::ConstructionError;
+    /// # use kernel::socketcan_frame::CanDataFrame;
+    /// # use kernel::socketcan_id::StandardId;
+    /// let id = StandardId::new(0x100).unwrap();
+    /// let mut frame = CanDataFrame::new(id, &[1, 2, 3]).unwrap();
+    ///
+    /// frame.set_data(&[1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+    /// assert_eq!(frame.dlc(), 8);
+    /// assert_eq!(frame.data(), &[1, 2, 3, 4, 5, 6, 7, 8]);
+    ///
+    /// assert_eq!(frame.set_data(&[0; 9]), Err(ConstructionError::TooMuchData));
+    /// assert_eq!(frame.dlc(), 8);
+    /// ```
+    pub fn set_data(&mut self, data: &[u8]) -> Result<(), ConstructionError> {
+        match data.len() {
+            n if n <= _CAN_MAX_DLEN as usize => {
+                self.0.can_dlc = n as u8;
+                self.0.data = [0u8; _CAN_MAX_DLEN as usize];
+                self.0.data[..n].copy_from_slice(data);
+                Ok(())
             }
-            TransceiverError => 0x0010,
-            NoAck => 0x0020,
-            BusOff => 0x0040,
-            BusError => 0x0080,
-            Restarted => 0x0100,
-            DecodingFailure(_failure) => 0,
-            Unknown(e) => e,
+            _ => Err(ConstructionError::TooMuchData),
+        }
+    }
+
+    /// Extends the data length to at least `end` bytes, zeroing any bytes
+    /// that are added.
+    fn extend_to(&mut self, end: usize) -> Result<(), ConstructionError> {
+        let dlc = self.0.can_dlc as usize;
+        if end > _CAN_MAX_DLEN as usize {
+            return Err(ConstructionError::TooMuchData);
+        }
+        if end > dlc {
+            self.0.data[dlc..end].fill(0);
+            self.0.can_dlc = end as u8;
+        }
+        Ok(())
+    }
+
+    /// Writes bytes into the data at the specified position, extending the
+    /// data length if needed.
+    fn set_bytes(&mut self, start_byte: usize, bytes: &[u8]) -> Result<(), ConstructionError> {
+        let end = start_byte + bytes.len();
+        self.extend_to(end)?;
+        self.0.data[start_byte..end].copy_from_slice(bytes);
+        Ok(())
+    }
+
+    /// Writes a little endian 16-bit value into the data, starting at the
+    /// specified byte.
+    ///
+    /// The data length is extended if needed to hold the value. This fails,
+    /// leaving the frame unchanged, if the value would extend past 8 bytes.
+    pub fn set_u16_le(&mut self, start_byte: usize, value: u16) -> Result<(), ConstructionError> {
+        self.set_bytes(start_byte, &value.to_le_bytes())
+    }
+
+    /// Writes a big endian 16-bit value into the data, starting at the
+    /// specified byte.
+    ///
+    /// The data length is extended if needed to hold the value. This fails,
+    /// leaving the frame unchanged, if the value would extend past 8 bytes.
+    pub fn set_u16_be(&mut self, start_byte: usize, value: u16) -> Result<(), ConstructionError> {
+        self.set_bytes(start_byte, &value.to_be_bytes())
+    }
+
+    /// Writes a little endian 32-bit value into the data, starting at the
+    /// specified byte.
+    ///
+    /// The data length is extended if needed to hold the value. This fails,
+    /// leaving the frame unchanged, if the value would extend past 8 bytes.
+    pub fn set_u32_le(&mut self, start_byte: usize, value: u32) -> Result<(), ConstructionError> {
+        self.set_bytes(start_byte, &value.to_le_bytes())
+    }
+
+    /// Writes a big endian 32-bit value into the data, starting at the
+    /// specified byte.
+    ///
+    /// The data length is extended if needed to hold the value. This fails,
+    /// leaving the frame unchanged, if the value would extend past 8 bytes.
+    pub fn set_u32_be(&mut self, start_byte: usize, value: u32) -> Result<(), ConstructionError> {
+        self.set_bytes(start_byte, &value.to_be_bytes())
+    }
+
+    /// Writes an unsigned bit field of up to 64 bits into the data.
+    ///
+    /// The start bit uses the DBC numbering for the byte order, as
+    /// described in [`crate::socketcan_signal`]. Only the lower `len` bits
+    /// of `value` are written. The data length is extended if needed to
+    /// hold the field. This fails, leaving the frame unchanged, if the field
+    /// would extend past 8 bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_embedded::Frame;
+    /// # use kernel::socketcan_frame::{CanDataFrame, Frame as _};
+    /// # use kernel::socketcan_id::StandardId;
+    /// let id = StandardId::new(0x100).unwrap();
+    /// let mut frame = CanDataFrame::new(id, &[]).unwrap();
+    ///
+    /// frame.set_bits(4, 8, true, 0xAB).unwrap();
+    /// assert_eq!(frame.data(), &[0xB0, 0x0A]);
+    /// assert_eq!(frame.extract_bits(4, 8, true), Some(0xAB));
+    ///
+    /// assert!(frame.set_bits(60, 8, true, 0xFF).is_err());
+    /// assert_eq!(frame.data(), &[0xB0, 0x0A]);
+    /// ```
+    pub fn set_bits(
+        &mut self,
+        start_bit: usize,
+        len: usize,
+        little_endian: bool,
+        value: u64,
+    ) -> Result<(), ConstructionError> {
+        if len == 0 || len > 64 {
+            return Err(ConstructionError::ValueOutOfRange);
+        }
+        // Check that the field fits the full buffer before extending.
+        crate::socketcan_signal::extract_bits(&self.0.data, start_bit, len, little_endian)
+            .ok_or(ConstructionError::TooMuchData)?;
+
+        let last_byte = if little_endian {
+            (start_bit + len - 1) / 8
+        } else {
+            // Fills the first byte down to bit 0, then whole bytes upwards.
+            let first_bits = start_bit % 8 + 1;
+            start_bit / 8 + (len.saturating_sub(first_bits) + 7) / 8
         };
-        Self::new_error(id, &data).unwrap()
+        self.extend_to(last_byte + 1)?;
+        crate::socketcan_signal::insert_bits(&mut self.0.data, start_bit, len, little_endian, value)
+            .ok_or(ConstructionError::TooMuchData)
+    }
+
+    /// A mutable slice into the actual data.
+    ///
+    /// This can be used to modify the payload in place, but not its length.
+    pub fn data_mut(&mut self) -> &mut [u8] {
+        &mut self.0.data[..(self.0.can_dlc as usize)]
+    }
+
+    /// Gets the full 8-byte data buffer of the frame, including any padding
+    /// past the data length.
+    ///
+    /// Normally `data()` should be used to get the payload.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_embedded::Frame;
+    /// # use kernel::socketcan_frame::CanDataFrame;
+    /// # use kernel::socketcan_id::StandardId;
+    /// let id = StandardId::new(0x100).unwrap();
+    /// let frame = CanDataFrame::new(id, &[1, 2, 3]).unwrap();
+    /// assert_eq!(frame.data().len(), 3);
+    /// assert_eq!(frame.data_full().len(), 8);
+    /// assert_eq!(frame.data_full(), &[1, 2, 3, 0, 0, 0, 0, 0]);
+    /// ```
+    pub fn data_full(&self) -> &[u8; 8] {
+        &self.0.data
+    }
+
+    /// Gets a copy of the full 8-byte data buffer of the frame, including
+    /// any padding past the data length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_embedded::Frame;
+    /// # use kernel::socketcan_frame::CanDataFrame;
+    /// # use kernel::socketcan_id::StandardId;
+    /// let id = StandardId::new(0x100).unwrap();
+    /// let frame = CanDataFrame::new(id, &[1, 2, 3]).unwrap();
+    /// assert_eq!(frame.data_array(), [1, 2, 3, 0, 0, 0, 0, 0]);
+    /// assert_eq!(&frame.data_array()[..frame.dlc()], frame.data());
+    /// ```
+    pub fn data_array(&self) -> [u8; 8] {
+        self.0.data
+    }
+
+    /// Checks that all the bytes of the frame past the data length are zero.
+    ///
+    /// Some strict receivers reject frames with non-zero padding.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_frame::{can_frame_default, CanDataFrame};
+    /// let mut raw = can_frame_default();
+    /// raw.can_id = 0x100;
+    /// raw.can_dlc = 2;
+    /// raw.data[..2].copy_from_slice(&[1, 2]);
+    /// assert!(CanDataFrame::try_from(raw).unwrap().has_clean_padding());
+    ///
+    /// raw.data[7] = 0xFF;
+    /// assert!(!CanDataFrame::try_from(raw).unwrap().has_clean_padding());
+    /// ```
+    pub fn has_clean_padding(&self) -> bool {
+        let n = (self.0.can_dlc as usize).min(_CAN_MAX_DLEN as usize);
+        self.0.data[n..].iter().all(|b| *b == 0)
+    }
+
+    /// Gets the worst-case number of bits that the frame occupies on the
+    /// bus, including stuff bits.
+    ///
+    /// A classic frame is sent entirely at the nominal bit rate, so this
+    /// takes no bit rate ratio. See [`classic_bit_length()`].
+    pub fn bit_length(&self) -> usize {
+        classic_bit_length(self.is_extended(), self.data().len())
+    }
+
+    /// Creates a data frame from its compact wire format.
+    ///
+    /// The compact format is the 4-byte ID word in little endian order,
+    /// including the EFF flag for an extended ID, followed by a 1-byte
+    /// length and then that many data bytes. The buffer must contain
+    /// exactly one frame.
+    ///
+    /// This fails with `WrongFrameType` if the buffer is truncated or has
+    /// trailing bytes, or if the ID word is marked as a remote or error
+    /// frame, with `IDTooLarge` if a standard ID word has bits set above
+    /// 0x7FF, and with `TooMuchData` if the length is greater than 8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_embedded::Frame;
+    /// # use kernel::socketcan_error::ConstructionError;
+    /// # use kernel::socketcan_frame::CanDataFrame;
+    /// # use kernel::socketcan_id::ExtendedId;
+    /// let id = ExtendedId::new(0x12345).unwrap();
+    /// let frame = CanDataFrame::new(id, &[1, 2, 3]).unwrap();
+    ///
+    /// let buf = frame.to_compact().unwrap();
+    /// assert_eq!(buf.len(), 8);
+    /// assert!(CanDataFrame::from_compact(&buf).unwrap() == frame);
+    ///
+    /// let err = CanDataFrame::from_compact(&buf[..7]).err();
+    /// assert_eq!(err, Some(ConstructionError::WrongFrameType));
+    ///
+    /// let err = CanDataFrame::from_compact(&[0x00, 0x08, 0, 0, 0]).err();
+    /// assert_eq!(err, Some(ConstructionError::IDTooLarge));
+    /// ```
+    pub fn from_compact(buf: &[u8]) -> Result<Self, ConstructionError> {
+        if buf.len() < 5 {
+            return Err(ConstructionError::WrongFrameType);
+        }
+        let (word, rest) = buf.split_at(4);
+        let word = canid_t::from_le_bytes([word[0], word[1], word[2], word[3]]);
+        let n = rest[0] as usize;
+        if n > _CAN_MAX_DLEN as usize {
+            return Err(ConstructionError::TooMuchData);
+        }
+        if rest.len() != n + 1 {
+            return Err(ConstructionError::WrongFrameType);
+        }
+        if word & _CAN_EFF_FLAG == 0 && word & _CAN_EFF_MASK > _CAN_SFF_MASK {
+            return Err(ConstructionError::IDTooLarge);
+        }
+        let frame = Self::init(word, &rest[1..])?;
+        Self::try_from(frame.0)
+    }
+
+    /// Gets the frame in its compact wire format.
+    ///
+    /// This is the reverse of `from_compact()`, and only includes the data
+    /// bytes that are in use.
+    pub fn to_compact(&self) -> crate::error::Result<Vec<u8>> {
+        let data = self.data();
+        let mut buf = Vec::try_with_capacity(5 + data.len())?;
+        buf.try_extend_from_slice(&self.0.can_id.to_le_bytes())?;
+        buf.try_push(data.len() as u8)?;
+        buf.try_extend_from_slice(data)?;
+        Ok(buf)
     }
 }
 
-impl AsRef<can_frame> for CanErrorFrame {
-    fn as_ref(&self) -> &can_frame {
+impl AsPtr for CanDataFrame {
+    type Inner = can_frame;
+
+    /// Gets a pointer to the CAN frame structure that is compatible with
+    /// the Linux C API.
+    fn as_ptr(&self) -> *const Self::Inner {
         &self.0
     }
+
+    /// Gets a mutable pointer to the CAN frame structure that is compatible
+    /// with the Linux C API.
+    fn as_mut_ptr(&mut self) -> *mut Self::Inner {
+        &mut self.0
+    }
+}
+
+impl crate::socketcan_embedded::Frame for CanDataFrame {
+    /// Create a new CAN 2.0 data frame
+    fn new(id: impl Into<Id>, data: &[u8]) -> Option<Self> {
+        let can_id = id_to_canid_t(id);
+        Self::init(can_id, data).ok()
+    }
+
+    /// Create a new remote transmission request frame.
+    /// This will always return None, as a data frame can not be remote.
+    fn new_remote(_id: impl Into<Id>, _dlc: usize) -> Option<Self> {
+        None
+    }
+
+    /// Check if frame uses 29-bit extended ID format.
+    fn is_extended(&self) -> bool {
+        self.0.can_id & _CAN_EFF_FLAG != 0
+    }
+
+    /// Check if frame is a remote transmission request.
+    fn is_remote_frame(&self) -> bool {
+        false
+    }
+
+    /// Return the frame identifier.
+    fn id(&self) -> Id {
+        id_from_canid_t(self.0.can_id)
+    }
+
+    /// Data length code
+    fn dlc(&self) -> usize {
+        self.0.can_dlc as usize
+    }
+
+    /// A slice into the actual data. Slice will always be <= 8 bytes in length
+    fn data(&self) -> &[u8] {
+        &self.0.data[..(self.0.can_dlc as usize)]
+    }
+}
+
+impl Frame for CanDataFrame {
+    /// Get the composite SocketCAN ID word, with EFF/RTR/ERR flags
+    fn id_word(&self) -> canid_t {
+        self.0.can_id
+    }
+
+    /// Sets the CAN ID for the frame
+    ///
+    /// This keeps any other flags, but sets or clears the EFF flag to match
+    /// the new ID.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_frame::{CanDataFrame, Frame, _CAN_EFF_FLAG};
+    /// # use kernel::socketcan_id::StandardId;
+    /// let mut frame = CanDataFrame::new_extended(0x1234, &[1, 2]).unwrap();
+    /// assert!(frame.is_extended());
+    ///
+    /// frame.set_id(StandardId::new(0x100).unwrap());
+    /// assert!(!frame.is_extended());
+    /// assert_eq!(frame.id_word() & _CAN_EFF_FLAG, 0);
+    /// assert_eq!(frame.id_word(), 0x100);
+    /// ```
+    fn set_id(&mut self, id: impl Into<Id>) {
+        self.0.can_id = canid_t_with_id(self.0.can_id, id);
+    }
+}
+
+impl TryFrom<can_frame> for CanDataFrame {
+    type Error = ConstructionError;
+
+    /// Try to create a `CanDataFrame` from a C `can_frame`
+    ///
+    /// This will only succeed the C frame is not marked as a remote or
+    /// error frame, and has a valid data length.
+    fn try_from(frame: can_frame) -> Result<Self, Self::Error> {
+        if frame.can_id & (_CAN_RTR_FLAG | _CAN_ERR_FLAG) != 0 {
+            Err(ConstructionError::WrongFrameType)
+        } else if frame.can_dlc as u32 > _CAN_MAX_DLEN {
+            Err(ConstructionError::TooMuchData)
+        } else {
+            Ok(Self(frame))
+        }
+    }
+}
+
+impl TryFrom<&[u8]> for CanDataFrame {
+    type Error = ConstructionError;
+
+    /// Try to create a `CanDataFrame` from its compact wire format.
+    ///
+    /// See `CanDataFrame::from_compact()`.
+    fn try_from(buf: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_compact(buf)
+    }
+}
+
+impl<'a> IntoIterator for &'a CanDataFrame {
+    type Item = &'a u8;
+    type IntoIter = core::slice::Iter<'a, u8>;
+
+    /// Iterates over the bytes of the payload.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_embedded::Frame;
+    /// # use kernel::socketcan_frame::CanDataFrame;
+    /// # use kernel::socketcan_id::StandardId;
+    /// let id = StandardId::new(0x100).unwrap();
+    /// let frame = CanDataFrame::new(id, &[1, 2, 3, 4]).unwrap();
+    ///
+    /// let mut sum = 0u32;
+    /// for b in &frame {
+    ///     sum += *b as u32;
+    /// }
+    /// assert_eq!(sum, 10);
+    /// assert_eq!(frame.dlc(), 4);
+    /// ```
+    fn into_iter(self) -> Self::IntoIter {
+        self.data().iter()
+    }
+}
+
+impl Default for CanDataFrame {
+    /// The default frame is a standard data frame, with an ID of zero and
+    /// no data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_embedded::Frame;
+    /// # use kernel::socketcan_frame::CanDataFrame;
+    /// let frame = CanDataFrame::default();
+    /// assert_eq!(frame.dlc(), 0);
+    /// assert!(frame.is_data_frame());
+    /// assert!(frame.data().is_empty());
+    /// ```
+    fn default() -> Self {
+        Self(can_frame_default())
+    }
+}
+
+impl AsRef<can_frame> for CanDataFrame {
+    fn as_ref(&self) -> &can_frame {
+        &self.0
+    }
+}
+
+impl PartialEq for CanDataFrame {
+    /// Frames are equal if they have the same ID word, length, and data.
+    /// Any padding beyond the data length is ignored.
+    fn eq(&self, other: &Self) -> bool {
+        self.0.can_id == other.0.can_id && self.data() == other.data()
+    }
+}
+
+impl Eq for CanDataFrame {}
+
+impl Hash for CanDataFrame {
+    /// Hashes the same fields that are compared for equality.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::collections::HashSet;
+    /// # use kernel::socketcan_frame::{can_frame_default, CanDataFrame};
+    /// let mut raw = can_frame_default();
+    /// raw.can_id = 0x100;
+    /// raw.can_dlc = 2;
+    /// raw.data[..2].copy_from_slice(&[1, 2]);
+    /// let a = CanDataFrame::try_from(raw).unwrap();
+    ///
+    /// // The same frame, with junk in the padding beyond the data length
+    /// raw.data[2..].copy_from_slice(&[0xAA; 6]);
+    /// let b = CanDataFrame::try_from(raw).unwrap();
+    ///
+    /// let set: HashSet<_> = [a, b].into_iter().collect();
+    /// assert_eq!(set.len(), 1);
+    /// ```
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.can_id.hash(state);
+        self.data().hash(state);
+    }
+}
+
+// ===== CyclicFrame =====
+
+/// A data frame template for periodic transmission with a rolling counter.
+///
+/// Many protocols include a counter in a fixed byte of the payload that is
+/// incremented on each transmission, so a receiver can detect lost or
+/// stale frames. Each call to `next()` increments the counter byte,
+/// wrapping from 255 to 0, and returns a copy of the updated frame.
+///
+/// # Examples
+///
+/// ```
+/// # use kernel::socketcan_embedded::Frame;
+/// # use kernel::socketcan_frame::{CanDataFrame, CyclicFrame};
+/// # use kernel::socketcan_id::StandardId;
+/// let id = StandardId::new(0x100).unwrap();
+/// let base = CanDataFrame::new(id, &[0xAA, 0xFE, 0xBB]).unwrap();
+/// let mut cyclic = CyclicFrame::new(base, 1).unwrap();
+///
+/// let frames: Vec<CanDataFrame> = cyclic.by_ref().take(3).collect();
+/// assert_eq!(frames[0].data(), &[0xAA, 0xFF, 0xBB]);
+/// assert_eq!(frames[1].data(), &[0xAA, 0x00, 0xBB]);
+/// assert_eq!(frames[2].data(), &[0xAA, 0x01, 0xBB]);
+/// assert_eq!(cyclic.counter(), 0x01);
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct CyclicFrame {
+    base: CanDataFrame,
+    counter_idx: usize,
+}
+
+impl CyclicFrame {
+    /// Creates a cyclic frame from a base frame and the index of the
+    /// counter byte in its payload.
+    ///
+    /// This fails if the counter byte is past the end of the data.
+    pub fn new(base: CanDataFrame, counter_idx: usize) -> Result<Self, ConstructionError> {
+        if counter_idx < base.0.can_dlc as usize {
+            Ok(Self { base, counter_idx })
+        } else {
+            Err(ConstructionError::TooMuchData)
+        }
+    }
+
+    /// Gets the current value of the counter.
+    pub fn counter(&self) -> u8 {
+        self.base.0.data[self.counter_idx]
+    }
+}
+
+impl Iterator for CyclicFrame {
+    type Item = CanDataFrame;
+
+    /// Increments the counter and returns the next frame to send.
+    fn next(&mut self) -> Option<Self::Item> {
+        let counter = &mut self.base.data_mut()[self.counter_idx];
+        *counter = counter.wrapping_add(1);
+        Some(self.base)
+    }
+}
+
+// ===== CanRemoteFrame =====
+
+/// The classic CAN 2.0 remote request frame.
+///
+/// This is is meant to request a transmission by another node on the bus.
+/// It contain no data, but carries the number of bytes requested in the
+/// data length code.
+///
+/// This is highly compatible with the `can_frame` from libc.
+/// ([ref](https://docs.rs/libc/latest/libc/struct.can_frame.html))
+#[derive(Clone, Copy)]
+pub struct CanRemoteFrame(can_frame);
+
+impl CanRemoteFrame {
+    /// Initializes a CAN remote frame from raw parts.
+    ///
+    /// The RTR flag is forced on. The `dlc` is the number of data bytes
+    /// being requested, which can not be more than 8.
+    pub fn init(can_id: canid_t, dlc: usize) -> Result<Self, ConstructionError> {
+        match dlc {
+            n if n <= _CAN_MAX_DLEN as usize => {
+                let mut frame = can_frame_default();
+                frame.can_id = can_id | _CAN_RTR_FLAG;
+                frame.can_dlc = n as u8;
+                Ok(Self(frame))
+            }
+            _ => Err(ConstructionError::TooMuchData),
+        }
+    }
+
+    /// Checks that the requested data length is valid for a classic frame.
+    ///
+    /// This is always true for frames created by this library, or checked
+    /// when converting from a raw `can_frame`, but is useful for code that
+    /// manipulates the frame through its raw pointer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_embedded::Frame;
+    /// # use kernel::socketcan_frame::{can_frame_default, CanRemoteFrame, _CAN_RTR_FLAG};
+    /// # use kernel::socketcan_id::StandardId;
+    /// let id = StandardId::new(0x100).unwrap();
+    /// assert!(CanRemoteFrame::new_remote(id, 9).is_none());
+    ///
+    /// let frame = CanRemoteFrame::new_remote(id, 8).unwrap();
+    /// assert!(frame.is_valid_dlc());
+    ///
+    /// let mut raw = can_frame_default();
+    /// raw.can_id = 0x100 | _CAN_RTR_FLAG;
+    /// raw.can_dlc = 9;
+    /// assert!(CanRemoteFrame::try_from(raw).is_err());
+    /// ```
+    pub fn is_valid_dlc(&self) -> bool {
+        self.0.can_dlc as u32 <= _CAN_MAX_DLEN
+    }
+
+    /// Gets the number of data bytes being requested.
+    ///
+    /// A responder should reply with a data frame of this length. This is
+    /// the data length code of the frame, and is distinct from `len()`,
+    /// which is always zero since a remote frame carries no data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_embedded::Frame as _;
+    /// # use kernel::socketcan_frame::{CanRemoteFrame, Frame};
+    /// let frame = CanRemoteFrame::remote_from_raw_id(0x100, 5).unwrap();
+    /// assert_eq!(frame.requested_dlc(), 5);
+    /// assert_eq!(frame.len(), 0);
+    /// assert!(frame.data().is_empty());
+    /// ```
+    pub fn requested_dlc(&self) -> usize {
+        self.0.can_dlc as usize
+    }
+
+    /// Gets the worst-case number of bits that the frame occupies on the
+    /// bus, including stuff bits.
+    ///
+    /// A remote frame sends no data field, whatever the requested length.
+    /// See [`classic_bit_length()`].
+    pub fn bit_length(&self) -> usize {
+        classic_bit_length(self.is_extended(), 0)
+    }
+
+    /// Sets the number of data bytes being requested.
+    ///
+    /// This fails if the `dlc` is greater than 8, leaving the frame
+    /// unchanged. The frame still carries no data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_embedded::Frame as _;
+    /// # use kernel::socketcan_error::ConstructionError;
+    /// # use kernel::socketcan_frame::{CanRemoteFrame, Frame};
+    /// let mut frame = CanRemoteFrame::remote_from_raw_id(0x100, 2).unwrap();
+    ///
+    /// assert_eq!(frame.set_dlc(8), Ok(()));
+    /// assert_eq!(frame.requested_dlc(), 8);
+    ///
+    /// assert_eq!(frame.set_dlc(9), Err(ConstructionError::TooMuchData));
+    /// assert_eq!(frame.requested_dlc(), 8);
+    /// assert!(frame.data().is_empty());
+    /// ```
+    pub fn set_dlc(&mut self, dlc: usize) -> Result<(), ConstructionError> {
+        match dlc {
+            n if n <= _CAN_MAX_DLEN as usize => {
+                self.0.can_dlc = n as u8;
+                Ok(())
+            }
+            _ => Err(ConstructionError::TooMuchData),
+        }
+    }
+}
+
+impl AsPtr for CanRemoteFrame {
+    type Inner = can_frame;
+
+    /// Gets a pointer to the CAN frame structure that is compatible with
+    /// the Linux C API.
+    fn as_ptr(&self) -> *const Self::Inner {
+        &self.0
+    }
+
+    /// Gets a mutable pointer to the CAN frame structure that is compatible
+    /// with the Linux C API.
+    fn as_mut_ptr(&mut self) -> *mut Self::Inner {
+        &mut self.0
+    }
+}
+
+impl crate::socketcan_embedded::Frame for CanRemoteFrame {
+    /// Create a new remote transmission request frame, requesting as many
+    /// bytes as are in the `data` slice. The data itself is not used.
+    fn new(id: impl Into<Id>, data: &[u8]) -> Option<Self> {
+        Self::new_remote(id, data.len())
+    }
+
+    /// Create a new remote transmission request frame.
+    ///
+    /// This will return `None` if the `dlc` is greater than 8.
+    fn new_remote(id: impl Into<Id>, dlc: usize) -> Option<Self> {
+        let can_id = id_to_canid_t(id);
+        Self::init(can_id, dlc).ok()
+    }
+
+    /// Check if frame uses 29-bit extended ID format.
+    fn is_extended(&self) -> bool {
+        self.0.can_id & _CAN_EFF_FLAG != 0
+    }
+
+    /// Check if frame is a remote transmission request.
+    fn is_remote_frame(&self) -> bool {
+        true
+    }
+
+    /// Return the frame identifier.
+    fn id(&self) -> Id {
+        id_from_canid_t(self.0.can_id)
+    }
+
+    /// Data length code
+    fn dlc(&self) -> usize {
+        self.0.can_dlc as usize
+    }
+
+    /// A remote frame does not carry any data.
+    /// This always returns an empty slice.
+    fn data(&self) -> &[u8] {
+        &[]
+    }
+}
+
+impl Frame for CanRemoteFrame {
+    /// Get the composite SocketCAN ID word, with EFF/RTR/ERR flags
+    fn id_word(&self) -> canid_t {
+        self.0.can_id
+    }
+
+    /// Sets the CAN ID for the frame
+    ///
+    /// This keeps the RTR flag, but sets or clears the EFF flag to match
+    /// the new ID.
+    fn set_id(&mut self, id: impl Into<Id>) {
+        self.0.can_id = canid_t_with_id(self.0.can_id, id);
+    }
+
+    /// Get the data length
+    ///
+    /// A remote frame carries no data, so this is always zero.
+    /// See `requested_dlc()` for the number of bytes being requested.
+    fn len(&self) -> usize {
+        0
+    }
+}
+
+impl TryFrom<can_frame> for CanRemoteFrame {
+    type Error = ConstructionError;
+
+    /// Try to create a `CanRemoteFrame` from a C `can_frame`
+    ///
+    /// This will only succeed the C frame is marked as a remote frame,
+    /// and requests a valid data length.
+    fn try_from(frame: can_frame) -> Result<Self, Self::Error> {
+        if frame.can_id & _CAN_RTR_FLAG == 0 || frame.can_id & _CAN_ERR_FLAG != 0 {
+            Err(ConstructionError::WrongFrameType)
+        } else if frame.can_dlc as u32 > _CAN_MAX_DLEN {
+            Err(ConstructionError::TooMuchData)
+        } else {
+            Ok(Self(frame))
+        }
+    }
+}
+
+impl AsRef<can_frame> for CanRemoteFrame {
+    fn as_ref(&self) -> &can_frame {
+        &self.0
+    }
+}
+
+impl PartialEq for CanRemoteFrame {
+    /// Frames are equal if they have the same ID word and requested length.
+    fn eq(&self, other: &Self) -> bool {
+        self.0.can_id == other.0.can_id && self.0.can_dlc == other.0.can_dlc
+    }
+}
+
+impl Eq for CanRemoteFrame {}
+
+impl Hash for CanRemoteFrame {
+    /// Hashes the same fields that are compared for equality.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.can_id.hash(state);
+        self.0.can_dlc.hash(state);
+    }
+}
+
+// ===== CanErrorFrame =====
+
+/// A SocketCAN error frame.
+///
+/// This is returned from a read/receive by the OS or interface device
+/// driver when it detects an error, such as a problem on the bus. The
+/// frame encodes detailed information about the error, which can be
+/// managed directly by the application or converted into a Rust error
+///
+/// This is highly compatible with the `can_frame` from libc.
+/// ([ref](https://docs.rs/libc/latest/libc/struct.can_frame.html))
+#[derive(Clone, Copy)]
+pub struct CanErrorFrame(can_frame);
+
+impl CanErrorFrame {
+    /// Creates a CAN error frame from raw parts.
+    ///
+    /// Note that an application would not normally _ever_ create an error
+    /// frame. This is included mainly to aid in implementing mocks and other
+    /// tests for an application.
+    ///
+    /// The data byte slice should have the necessary codes for the supplied
+    /// error. They will be zero padded to a full frame of 8 bytes.
+    ///
+    /// Also note:
+    /// - The error flag is forced on
+    /// - The other, non-error, flags are forced off
+    /// - The frame data is always padded with zero's to 8 bytes,
+    ///   regardless of the length of the `data` parameter provided.
+    pub fn new_error(can_id: canid_t, data: &[u8]) -> Result<Self, ConstructionError> {
+        match data.len() {
+            n if n <= _CAN_MAX_DLEN as usize => {
+                let mut frame = can_frame_default();
+                frame.can_id = (can_id & _CAN_ERR_MASK) | _CAN_ERR_FLAG;
+                frame.can_dlc = _CAN_MAX_DLEN as u8;
+                frame.data[..n].copy_from_slice(data);
+                Ok(Self(frame))
+            }
+            _ => Err(ConstructionError::TooMuchData),
+        }
+    }
+
+    /// Return the error bits from the ID word of the error frame.
+    pub fn error_bits(&self) -> u32 {
+        self.id_word() & _CAN_ERR_MASK
+    }
+
+    /// Converts this error frame into a `CanError`
+    pub fn into_error(self) -> CanError {
+        CanError::from(self)
+    }
+
+    /// Creates an error frame that represents an embedded `ErrorKind`.
+    ///
+    /// This is mainly for mocks that need to simulate a specific kind of
+    /// error. The kinds map to error classes as:
+    /// - `Overrun` - a controller problem, with an RX buffer overflow
+    /// - `FrameFormat` - a protocol violation, with a form error
+    /// - `Parity` - a protocol violation in the CRC sequence
+    /// - `Noise` - a bus error
+    /// - `Other` - an unspecified controller problem
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_embedded::ErrorKind;
+    /// # use kernel::socketcan_frame::CanErrorFrame;
+    /// let class = |kind| CanErrorFrame::from_error_kind(kind).error_bits();
+    /// assert_eq!(class(ErrorKind::Overrun), 0x0004);
+    /// assert_eq!(class(ErrorKind::FrameFormat), 0x0008);
+    /// assert_eq!(class(ErrorKind::Parity), 0x0008);
+    /// assert_eq!(class(ErrorKind::Noise), 0x0080);
+    /// assert_eq!(class(ErrorKind::Other), 0x0004);
+    /// ```
+    pub fn from_error_kind(kind: ErrorKind) -> Self {
+        let err = match kind {
+            ErrorKind::Overrun => {
+                CanError::ControllerProblem(ControllerProblem::ReceiveBufferOverflow)
+            }
+            ErrorKind::FrameFormat => CanError::ProtocolViolation {
+                vtype: ViolationType::FrameFormatError,
+                location: Location::Unspecified,
+            },
+            ErrorKind::Parity => CanError::ProtocolViolation {
+                vtype: ViolationType::Unspecified,
+                location: Location::CrcSequence,
+            },
+            ErrorKind::Noise => CanError::BusError,
+            ErrorKind::Other => CanError::ControllerProblem(ControllerProblem::Unspecified),
+        };
+        Self::from(err)
+    }
+}
+
+impl AsPtr for CanErrorFrame {
+    type Inner = can_frame;
+
+    /// Gets a pointer to the CAN frame structure that is compatible with
+    /// the Linux C API.
+    fn as_ptr(&self) -> *const Self::Inner {
+        &self.0
+    }
+
+    /// Gets a mutable pointer to the CAN frame structure that is compatible
+    /// with the Linux C API.
+    fn as_mut_ptr(&mut self) -> *mut Self::Inner {
+        &mut self.0
+    }
+}
+
+impl crate::socketcan_embedded::Frame for CanErrorFrame {
+    /// Create an error frame.
+    ///
+    /// Note that an application would not normally _ever_ create an error
+    /// frame. This is included mainly to aid in implementing mocks and other
+    /// tests for an application.
+    ///
+    /// This will set the error bit in the CAN ID word.
+    fn new(id: impl Into<Id>, data: &[u8]) -> Option<Self> {
+        let can_id = id_to_canid_t(id);
+        Self::new_error(can_id, data).ok()
+    }
+    /// Return the error class bits as the frame identifier.
+    fn id(&self) -> Id {
+        id_from_raw(self.error_bits()).unwrap_or(Id::Standard(StandardId::ZERO))
+    }
+
+    /// The application should not create an error frame.
+    /// This will always return None.
+    fn new_remote(_id: impl Into<Id>, _dlc: usize) -> Option<Self> {
+        None
+    }
+
+    /// Check if frame uses 29-bit extended ID format.
+    fn is_extended(&self) -> bool {
+        self.0.can_id & _CAN_EFF_FLAG != 0
+    }
+
+    /// Check if frame is a remote transmission request.
+    fn is_remote_frame(&self) -> bool {
+        false
+    }
+
+    /// Check if frame is a data frame.
+    fn is_data_frame(&self) -> bool {
+        false
+    }
+
+    /// Data length code
+    fn dlc(&self) -> usize {
+        self.0.can_dlc as usize
+    }
+
+    /// A slice into the actual data.
+    /// An error frame can always acess the full 8-byte data payload.
+    fn data(&self) -> &[u8] {
+        &self.0.data[..]
+    }
+}
+
+impl Frame for CanErrorFrame {
+    /// Get the composite SocketCAN ID word, with EFF/RTR/ERR flags
+    fn id_word(&self) -> canid_t {
+        self.0.can_id
+    }
+
+    /// Return the error class bits, without the ERR flag.
+    fn raw_id(&self) -> canid_t {
+        self.error_bits()
+    }
+
+    /// Sets the CAN ID for the frame
+    /// This does nothing on an error frame.
+    fn set_id(&mut self, _id: impl Into<Id>) {}
+}
+
+impl CanErrorFrame {
+    /// Sets the data payload of the frame.
+    /// This is an error on an error frame.
+    fn set_data(&mut self, _data: &[u8]) -> Result<(), ConstructionError> {
+        Err(ConstructionError::WrongFrameType)
+    }
+    fn data(&self) -> &[u8] {
+        &self.0.data[..]
+    }
+}
+/* 
+impl core::fmt::Debug for CanErrorFrame {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "CanErrorFrame {{ ")?;
+        core::fmt::UpperHex::fmt(self, f)?;
+        write!(f, " }}")
+    }
+}*/
+/* 
+impl core::fmt::UpperHex for CanErrorFrame {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+        write!(f, "{:X}#", self.0.can_id)?;
+        let mut parts = self.data().iter().map(|v| alloc::format!("{:02X}", v));
+        write!(f, "{}", parts.join(" "))
+    }
+}
+*/
+impl TryFrom<can_frame> for CanErrorFrame {
+    type Error = ConstructionError;
+
+    /// Try to create a `CanErrorFrame` from a C `can_frame`
+    ///
+    /// This will only succeed the C frame is marked as an error frame,
+    /// and has a valid data length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_error::ConstructionError;
+    /// # use kernel::socketcan_frame::*;
+    /// let mut raw = can_frame_default();
+    /// raw.can_dlc = 9;
+    ///
+    /// raw.can_id = _CAN_ERR_FLAG;
+    /// assert_eq!(CanErrorFrame::try_from(raw).err(), Some(ConstructionError::TooMuchData));
+    ///
+    /// raw.can_id = 0x100;
+    /// assert_eq!(CanDataFrame::try_from(raw).err(), Some(ConstructionError::TooMuchData));
+    ///
+    /// raw.can_id = 0x100 | _CAN_RTR_FLAG;
+    /// assert_eq!(CanRemoteFrame::try_from(raw).err(), Some(ConstructionError::TooMuchData));
+    /// ```
+    fn try_from(frame: can_frame) -> Result<Self, Self::Error> {
+        if frame.can_id & _CAN_ERR_FLAG == 0 {
+            Err(ConstructionError::WrongFrameType)
+        } else if frame.can_dlc as u32 > _CAN_MAX_DLEN {
+            Err(ConstructionError::TooMuchData)
+        } else {
+            Ok(Self(frame))
+        }
+    }
+}
+
+impl From<CanError> for CanErrorFrame {
+    fn from(err: CanError) -> Self {
+        use CanError::*;
+
+        let mut data = [0u8; _CAN_MAX_DLEN as usize ];
+        let id: canid_t = match err {
+            TransmitTimeout => 0x0001,
+            LostArbitration(bit) => {
+                data[0] = bit;
+                0x0002
+            }
+            ControllerProblem(prob) => {
+                data[1] = prob as u8;
+                0x0004
+            }
+            ProtocolViolation { vtype, location } => {
+                data[2] = vtype as u8;
+                data[3] = location as u8;
+                0x0008
+            }
+            TransceiverError => 0x0010,
+            NoAck => 0x0020,
+            BusOff => 0x0040,
+            BusError => 0x0080,
+            Restarted => 0x0100,
+            DecodingFailure(_failure) => 0,
+            Unknown(e) => e,
+        };
+        Self::new_error(id, &data).unwrap()
+    }
+}
+
+impl AsRef<can_frame> for CanErrorFrame {
+    fn as_ref(&self) -> &can_frame {
+        &self.0
+    }
+}
+
+impl PartialEq for CanErrorFrame {
+    /// Frames are equal if they have the same ID word, length, and data.
+    /// An error frame always carries the full 8-byte payload.
+    fn eq(&self, other: &Self) -> bool {
+        self.0.can_id == other.0.can_id
+            && self.0.can_dlc == other.0.can_dlc
+            && self.0.data == other.0.data
+    }
+}
+
+impl Eq for CanErrorFrame {}
+
+impl Hash for CanErrorFrame {
+    /// Hashes the same fields that are compared for equality.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.can_id.hash(state);
+        self.0.can_dlc.hash(state);
+        self.0.data.hash(state);
+    }
+}
+
+// ===== CanFdFrame =====
+
+/// The CAN flexible data rate frame with up to 64-bytes of data.
+///
+/// This is highly compatible with the `canfd_frame` from libc.
+/// ([ref](https://docs.rs/libc/latest/libc/struct.canfd_frame.html))
+///
+/// Payloads greater than 8 bytes in length must be one of the sizes that
+/// can be encoded in the 4-bit DLC of an FD frame. See [`fd_len_to_dlc()`].
+///
+/// # Examples
+///
+/// ```
+/// # use kernel::socketcan_frame::{CanFdFrame, FdFlags, Frame, _CANFD_BRS};
+/// # use kernel::socketcan_id::StandardId;
+/// let id = StandardId::new(0x100).unwrap();
+/// let frame = CanFdFrame::with_flags(id, &[1, 2, 3, 4, 5, 6, 7, 8, 9], FdFlags::new(_CANFD_BRS))
+///     .unwrap();
+///
+/// let copy = Clone::clone(&frame);
+/// assert!(copy == frame);
+/// assert!(copy.is_brs());
+/// assert_eq!(copy.id_word(), frame.id_word());
+/// assert_eq!(copy.data_full(), frame.data_full());
+/// ```
+#[derive(Clone, Copy)]
+pub struct CanFdFrame(canfd_frame);
+
+impl CanFdFrame {
+    /// The maximum data length code of an FD frame.
+    ///
+    /// As with `dlc()`, this is given as the data length in bytes, rather
+    /// than the 4-bit code that is sent on the bus.
+    pub const MAX_DLC: usize = _CANFD_MAX_DLEN as usize;
+
+    /// The maximum length of the data in an FD frame, in bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_frame::{CanDataFrame, CanFdFrame};
+    /// kernel::static_assert!(CanDataFrame::MAX_DLC == 8);
+    /// kernel::static_assert!(CanDataFrame::MAX_DLEN == 8);
+    /// kernel::static_assert!(CanFdFrame::MAX_DLC == 64);
+    /// kernel::static_assert!(CanFdFrame::MAX_DLEN == 64);
+    ///
+    /// // The constants can size buffers at compile time
+    /// let buf = [0u8; CanFdFrame::MAX_DLEN];
+    /// assert_eq!(buf.len(), 64);
+    /// ```
+    pub const MAX_DLEN: usize = _CANFD_MAX_DLEN as usize;
+
+    /// Initializes a CAN FD frame from raw parts.
+    ///
+    /// The ID word is used as-is, so it should not have the RTR or ERR
+    /// flags set, as neither are valid for an FD frame.
+    ///
+    /// If the length of the data can't be encoded in an FD DLC, the payload
+    /// is padded with zeros up to the next valid length.
+    pub fn init(can_id: canid_t, data: &[u8], fd_flags: FdFlags) -> Result<Self, ConstructionError> {
+        let n = data.len();
+        let len = fd_len_to_dlc(n)
+            .map(fd_dlc_to_len)
+            .ok_or(ConstructionError::TooMuchData)?;
+
+        let mut frame = canfd_frame_default();
+        frame.can_id = can_id;
+        frame.len = len as u8;
+        frame.flags = fd_flags.flags as u8;
+        frame.data[..n].copy_from_slice(data);
+        Ok(Self(frame))
+    }
+
+    /// Creates a new FD frame with the specified FD flags.
+    pub fn with_flags(id: impl Into<Id>, data: &[u8], fd_flags: FdFlags) -> Option<Self> {
+        let can_id = id_to_canid_t(id);
+        Self::init(can_id, data, fd_flags).ok()
+    }
+
+    /// Creates an FD frame from a C `canfd_frame`, keeping the length
+    /// exactly as reported.
+    ///
+    /// This is meant for capture tools that must faithfully record what
+    /// the kernel delivered. No validation is done on the frame, and the
+    /// length is not snapped to one that can be encoded in an FD DLC, so the
+    /// resulting frame may not be valid for transmission.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_frame::{canfd_frame_default, CanFdFrame};
+    /// let mut raw = canfd_frame_default();
+    /// raw.can_id = 0x100;
+    /// raw.len = 33;
+    ///
+    /// let frame = CanFdFrame::from_raw_preserving_len(raw);
+    /// assert_eq!(frame.raw_len(), 33);
+    /// ```
+    pub fn from_raw_preserving_len(frame: canfd_frame) -> Self {
+        Self(frame)
+    }
+
+    /// Gets the length of the data exactly as stored in the frame.
+    pub fn raw_len(&self) -> usize {
+        self.0.len as usize
+    }
+
+    /// The number of bytes that can still be added to the data.
+    pub fn remaining_capacity(&self) -> usize {
+        Self::MAX_DLEN.saturating_sub(self.0.len as usize)
+    }
+
+    /// Appends bytes to the end of the data.
+    ///
+    /// The length of the frame is increased to the next valid FD length
+    /// that holds the new data, padding with zeros if necessary. This fails,
+    /// leaving the frame unchanged, if the data would not fit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_embedded::Frame;
+    /// # use kernel::socketcan_error::ConstructionError;
+    /// # use kernel::socketcan_frame::CanFdFrame;
+    /// # use kernel::socketcan_id::StandardId;
+    /// let id = StandardId::new(0x100).unwrap();
+    /// let mut frame = CanFdFrame::new(id, &[0; 8]).unwrap();
+    /// assert_eq!(frame.remaining_capacity(), CanFdFrame::MAX_DLEN - 8);
+    ///
+    /// // Nine bytes is not a valid FD length, so it is padded to twelve
+    /// frame.push_data(&[1]).unwrap();
+    /// assert_eq!(frame.dlc(), 12);
+    /// assert_eq!(&frame.data()[8..], &[1, 0, 0, 0]);
+    /// assert_eq!(frame.remaining_capacity(), 52);
+    ///
+    /// assert_eq!(frame.push_data(&[0; 53]), Err(ConstructionError::TooMuchData));
+    /// assert_eq!(frame.dlc(), 12);
+    /// ```
+    pub fn push_data(&mut self, extra: &[u8]) -> Result<(), ConstructionError> {
+        let start = self.0.len as usize;
+        let end = start + extra.len();
+        let len = fd_len_to_dlc(end)
+            .map(fd_dlc_to_len)
+            .ok_or(ConstructionError::TooMuchData)?;
+
+        self.0.data[start..end].copy_from_slice(extra);
+        self.0.data[end..len].fill(0);
+        self.0.len = len as u8;
+        Ok(())
+    }
+
+    /// Gets the full 64-byte data buffer of the frame, including any
+    /// padding past the data length.
+    ///
+    /// Normally `data()` should be used to get the payload.
+    pub fn data_full(&self) -> &[u8; 64] {
+        &self.0.data
+    }
+
+    /// Gets a copy of the full 64-byte data buffer of the frame, including
+    /// any padding past the data length.
+    pub fn data_array(&self) -> [u8; 64] {
+        self.0.data
+    }
+
+    /// Gets the FD flags of the frame.
+    pub fn fd_flags(&self) -> FdFlags {
+        FdFlags::new(self.0.flags as u32)
+    }
+
+    /// Whether the frame uses a bit rate switch for the data phase.
+    pub fn is_brs(&self) -> bool {
+        self.fd_flags().is_brs()
+    }
+
+    /// Sets whether the frame uses a bit rate switch for the data phase.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_embedded::Frame;
+    /// # use kernel::socketcan_frame::CanFdFrame;
+    /// # use kernel::socketcan_id::StandardId;
+    /// let id = StandardId::new(0x100).unwrap();
+    /// let mut frame = CanFdFrame::new(id, &[1, 2, 3]).unwrap();
+    /// assert!(!frame.is_brs());
+    ///
+    /// frame.set_brs(true);
+    /// assert!(frame.is_brs());
+    /// assert!(frame.fd_flags().is_brs());
+    /// assert!(!frame.is_esi());
+    ///
+    /// frame.set_esi(true);
+    /// frame.set_brs(false);
+    /// assert!(!frame.is_brs());
+    /// assert!(frame.is_esi());
+    /// ```
+    pub fn set_brs(&mut self, on: bool) {
+        self.set_flag(_CANFD_BRS, on);
+    }
+
+    /// Gets the error state indicator of the transmitting node.
+    pub fn is_esi(&self) -> bool {
+        self.fd_flags().is_esi()
+    }
+
+    /// Sets the error state indicator.
+    ///
+    /// This is normally set by the controller of the transmitting node when
+    /// it is error passive, so it would be unusual to set it from software.
+    pub fn set_esi(&mut self, on: bool) {
+        self.set_flag(_CANFD_ESI, on);
+    }
+
+    /// Gets the worst-case number of bits that the frame occupies on the
+    /// bus, as `(nominal, data)`.
+    ///
+    /// When the bit rate switch is set, the `data` bits are sent at the
+    /// data bit rate. See [`fd_bit_length()`].
+    pub fn bit_length(&self) -> (usize, usize) {
+        fd_bit_length(self.is_extended(), self.data().len(), self.is_brs())
+    }
+
+    fn set_flag(&mut self, flag: u32, on: bool) {
+        if on {
+            self.0.flags |= flag as u8;
+        } else {
+            self.0.flags &= !(flag as u8);
+        }
+    }
+
+    /// Splits a large payload into a sequence of FD frames.
+    ///
+    /// The first data byte of each frame is a sequence number, starting at
+    /// zero and wrapping at 255, leaving up to 63 bytes of the payload per
+    /// frame. Near the end of the payload, chunks are sized so that every
+    /// frame has a valid FD length without padding, which lets the payload
+    /// be reassembled exactly with [`CanFdFrame::reassemble()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_frame::CanFdFrame;
+    /// # use kernel::socketcan_id::StandardId;
+    /// let id = StandardId::new(0x100).unwrap();
+    /// let buf: Vec<u8> = (0..200).map(|i| i as u8).collect();
+    ///
+    /// let frames = CanFdFrame::chunk_large(id, &buf).unwrap();
+    /// assert!(frames.len() > 3);
+    /// assert_eq!(CanFdFrame::reassemble(&frames).unwrap(), buf);
+    /// ```
+    pub fn chunk_large(id: impl Into<Id>, buf: &[u8]) -> crate::error::Result<Vec<Self>> {
+        let can_id = id_to_canid_t(id);
+        let mut frames = Vec::new();
+        let mut chunk = [0u8; _CANFD_MAX_DLEN as usize];
+        let (mut rest, mut seq) = (buf, 0u8);
+
+        while !rest.is_empty() {
+            let max = (rest.len() + 1).min(_CANFD_MAX_DLEN as usize);
+            let len = FD_DLC_LEN
+                .iter()
+                .rev()
+                .map(|&n| n as usize)
+                .find(|&n| n <= max)
+                .unwrap_or(0);
+            let (head, tail) = rest.split_at(len - 1);
+
+            chunk[0] = seq;
+            chunk[1..len].copy_from_slice(head);
+            let frame = Self::init(can_id, &chunk[..len], FdFlags::new(0))
+                .map_err(|_| crate::error::code::EINVAL)?;
+            frames.try_push(frame)?;
+
+            rest = tail;
+            seq = seq.wrapping_add(1);
+        }
+        Ok(frames)
+    }
+
+    /// Reassembles a payload that was split by [`CanFdFrame::chunk_large()`].
+    ///
+    /// This fails with `EINVAL` if a frame is empty or if the frames are not
+    /// in sequence.
+    pub fn reassemble(frames: &[Self]) -> crate::error::Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        for (i, frame) in frames.iter().enumerate() {
+            match frame.data().split_first() {
+                Some((&seq, data)) if seq == i as u8 => buf.try_extend_from_slice(data)?,
+                _ => return Err(crate::error::code::EINVAL),
+            }
+        }
+        Ok(buf)
+    }
+}
+
+impl AsPtr for CanFdFrame {
+    type Inner = canfd_frame;
+
+    /// Gets a pointer to the CAN FD frame structure that is compatible with
+    /// the Linux C API.
+    fn as_ptr(&self) -> *const Self::Inner {
+        &self.0
+    }
+
+    /// Gets a mutable pointer to the CAN FD frame structure that is
+    /// compatible with the Linux C API.
+    fn as_mut_ptr(&mut self) -> *mut Self::Inner {
+        &mut self.0
+    }
+}
+
+impl crate::socketcan_embedded::Frame for CanFdFrame {
+    /// Create a new FD frame, with no FD flags set.
+    fn new(id: impl Into<Id>, data: &[u8]) -> Option<Self> {
+        Self::with_flags(id, data, FdFlags::new(0))
+    }
+
+    /// CAN FD frames don't support remote.
+    /// This will always return None.
+    fn new_remote(_id: impl Into<Id>, _dlc: usize) -> Option<Self> {
+        None
+    }
+
+    /// Check if frame uses 29-bit extended ID format.
+    fn is_extended(&self) -> bool {
+        self.0.can_id & _CAN_EFF_FLAG != 0
+    }
+
+    /// The FD frames don't support remote request.
+    fn is_remote_frame(&self) -> bool {
+        false
+    }
+
+    /// Return the frame identifier.
+    fn id(&self) -> Id {
+        id_from_canid_t(self.0.can_id)
+    }
+
+    /// Data length code.
+    /// For an FD frame this is the length of the data, in bytes.
+    fn dlc(&self) -> usize {
+        self.0.len as usize
+    }
+
+    /// A slice into the actual data. Slice will always be <= 64 bytes in length
+    fn data(&self) -> &[u8] {
+        let n = (self.0.len as usize).min(_CANFD_MAX_DLEN as usize);
+        &self.0.data[..n]
+    }
+}
+
+impl Frame for CanFdFrame {
+    /// Get the composite SocketCAN ID word, with EFF/RTR/ERR flags
+    fn id_word(&self) -> canid_t {
+        self.0.can_id
+    }
+
+    /// Sets the CAN ID for the frame
+    ///
+    /// This keeps any other flags, but sets or clears the EFF flag to match
+    /// the new ID.
+    fn set_id(&mut self, id: impl Into<Id>) {
+        self.0.can_id = canid_t_with_id(self.0.can_id, id);
+    }
+}
+
+impl TryFrom<canfd_frame> for CanFdFrame {
+    type Error = ConstructionError;
+
+    /// Try to create a `CanFdFrame` from a C `canfd_frame`
+    ///
+    /// This will fail if the C frame is marked as a remote or error frame,
+    /// or if the data length is more than 64 bytes.
+    fn try_from(frame: canfd_frame) -> Result<Self, Self::Error> {
+        if frame.can_id & (_CAN_RTR_FLAG | _CAN_ERR_FLAG) != 0 {
+            Err(ConstructionError::WrongFrameType)
+        } else if frame.len as u32 > _CANFD_MAX_DLEN {
+            Err(ConstructionError::TooMuchData)
+        } else {
+            Ok(Self(frame))
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a CanFdFrame {
+    type Item = &'a u8;
+    type IntoIter = core::slice::Iter<'a, u8>;
+
+    /// Iterates over the bytes of the payload.
+    fn into_iter(self) -> Self::IntoIter {
+        self.data().iter()
+    }
+}
+
+impl Default for CanFdFrame {
+    /// The default frame is a standard FD frame, with an ID of zero, no
+    /// data, and no FD flags set.
+    fn default() -> Self {
+        Self(canfd_frame_default())
+    }
+}
+
+impl AsRef<canfd_frame> for CanFdFrame {
+    fn as_ref(&self) -> &canfd_frame {
+        &self.0
+    }
+}
+
+impl PartialEq for CanFdFrame {
+    /// Frames are equal if they have the same ID word, FD flags, length,
+    /// and data.
+    /// Any padding beyond the data length is ignored.
+    fn eq(&self, other: &Self) -> bool {
+        self.0.can_id == other.0.can_id
+            && self.0.flags == other.0.flags
+            && self.data() == other.data()
+    }
+}
+
+impl Eq for CanFdFrame {}
+
+impl Hash for CanFdFrame {
+    /// Hashes the same fields that are compared for equality.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.can_id.hash(state);
+        self.0.flags.hash(state);
+        self.data().hash(state);
+    }
+}
+
+// ===== CanXlFrame =====
+
+/// The CAN XL frame with 1 to 2048 bytes of data.
+///
+/// This is compatible with the kernel `canxl_frame`. Instead of a CAN ID,
+/// an XL frame has an 11-bit priority used for arbitration, along with a
+/// virtual CAN network ID (VCID), an SDU type (SDT) describing the payload,
+/// and a 32-bit acceptance field (AF) used for filtering.
+///
+/// Support for CAN XL depends on the kernel and the controller, so this
+/// is only available with the `canxl` feature.
+///
+/// The frame is over 2 KiB, which is too much for the kernel stack, so it
+/// is only created on the heap, with `new_boxed()`, and filled in place
+/// with `init_xl()`.
+///
+/// # Examples
+///
+/// ```
+/// # use kernel::socketcan_frame::CanXlFrame;
+/// let mut frame = CanXlFrame::new_boxed().unwrap();
+/// frame.init_xl(0x123, 0x01, 0xdead_beef, &[1, 2, 3]).unwrap();
+/// assert_eq!(frame.priority(), 0x123);
+/// assert_eq!(frame.af(), 0xdead_beef);
+/// assert_eq!(frame.data(), &[1, 2, 3]);
+///
+/// frame.set_vcid(7);
+/// frame.init_xl(0x456, 0x01, 0, &[4]).unwrap();
+/// assert_eq!(frame.priority(), 0x456);
+/// assert_eq!(frame.vcid(), 7);
+///
+/// assert!(frame.init_xl(0x800, 0x01, 0, &[1]).is_err());
+/// assert!(frame.init_xl(0x123, 0x01, 0, &[]).is_err());
+/// ```
+#[cfg(feature = "canxl")]
+pub struct CanXlFrame(canxl_frame);
+
+#[cfg(feature = "canxl")]
+impl CanXlFrame {
+    /// Allocates an empty, zeroed CAN XL frame on the heap.
+    ///
+    /// The frame is not valid until it is filled in by `init_xl()`.
+    pub fn new_boxed() -> crate::error::Result<Box<Self>> {
+        let frame = Box::<Self>::try_new_zeroed()?;
+        // SAFETY: All zeros is a valid `canxl_frame`, and `CanXlFrame` is
+        // a plain wrapper around it.
+        Ok(unsafe { Box::from_raw(Box::into_raw(frame) as *mut Self) })
+    }
+
+    /// Fills in the frame in place, replacing its contents.
+    ///
+    /// The VCID, which shares a word with the priority, is kept. The XLF
+    /// flag is always set. This fails, leaving the frame unchanged,
+    /// if the priority is more than 11 bits, or the data is empty or longer
+    /// than 2048 bytes.
+    pub fn init_xl(&mut self, prio: u16, sdt: u8, af: u32, data: &[u8]) -> Result<(), ConstructionError> {
+        if prio as u32 > _CANXL_PRIO_MASK {
+            return Err(ConstructionError::IDTooLarge);
+        }
+        match data.len() {
+            n if n >= _CANXL_MIN_DLEN as usize && n <= _CANXL_MAX_DLEN as usize => {
+                let old = self.data().len();
+                let frame = &mut self.0;
+                frame.prio =
+                    (frame.prio & !_CANXL_PRIO_MASK) | (prio as canid_t & _CANXL_PRIO_MASK);
+                frame.flags = _CANXL_XLF as u8;
+                frame.sdt = sdt;
+                frame.len = n as u16;
+                frame.af = af;
+                frame.data[..n].copy_from_slice(data);
+                if old > n {
+                    frame.data[n..old].fill(0);
+                }
+                Ok(())
+            }
+            _ => Err(ConstructionError::TooMuchData),
+        }
+    }
+
+    /// Gets the 11-bit priority of the frame.
+    pub fn priority(&self) -> u16 {
+        (self.0.prio & _CANXL_PRIO_MASK) as u16
+    }
+
+    /// Gets the virtual CAN network ID.
+    pub fn vcid(&self) -> u8 {
+        ((self.0.prio & _CANXL_VCID_MASK) >> _CANXL_VCID_OFFSET) as u8
+    }
+
+    /// Sets the virtual CAN network ID.
+    pub fn set_vcid(&mut self, vcid: u8) {
+        self.0.prio = (self.0.prio & !_CANXL_VCID_MASK) | ((vcid as u32) << _CANXL_VCID_OFFSET);
+    }
+
+    /// Gets the SDU type, which describes the content of the payload.
+    pub fn sdt(&self) -> u8 {
+        self.0.sdt
+    }
+
+    /// Gets the acceptance field.
+    pub fn af(&self) -> u32 {
+        self.0.af
+    }
+
+    /// Whether the simple extended content (SEC) flag is set.
+    pub fn is_sec(&self) -> bool {
+        self.0.flags as u32 & _CANXL_SEC != 0
+    }
+
+    /// Gets the length of the data, in bytes.
+    pub fn len(&self) -> usize {
+        self.0.len as usize
+    }
+
+    /// Whether the frame has no data.
+    /// This is never true for a valid XL frame.
+    pub fn is_empty(&self) -> bool {
+        self.0.len == 0
+    }
+
+    /// A slice into the actual data.
+    pub fn data(&self) -> &[u8] {
+        let n = (self.0.len as usize).min(_CANXL_MAX_DLEN as usize);
+        &self.0.data[..n]
+    }
+}
+
+#[cfg(feature = "canxl")]
+impl AsPtr for CanXlFrame {
+    type Inner = canxl_frame;
+
+    /// Gets a pointer to the CAN XL frame structure that is compatible with
+    /// the Linux C API.
+    fn as_ptr(&self) -> *const Self::Inner {
+        &self.0
+    }
+
+    /// Gets a mutable pointer to the CAN XL frame structure that is
+    /// compatible with the Linux C API.
+    fn as_mut_ptr(&mut self) -> *mut Self::Inner {
+        &mut self.0
+    }
+
+    /// The size of the frame header plus the data actually in use, which
+    /// is what is sent to the kernel for an XL frame.
+    fn size(&self) -> usize {
+        core::mem::size_of::<canxl_frame>() - _CANXL_MAX_DLEN as usize + self.len()
+    }
+}
+
+#[cfg(feature = "canxl")]
+impl TryFrom<canxl_frame> for CanXlFrame {
+    type Error = ConstructionError;
+
+    /// Try to create a `CanXlFrame` from a C `canxl_frame`
+    ///
+    /// This will fail if the XLF flag is not set, or if the data length is
+    /// not valid.
+    fn try_from(frame: canxl_frame) -> Result<Self, Self::Error> {
+        if frame.flags as u32 & _CANXL_XLF == 0 {
+            Err(ConstructionError::WrongFrameType)
+        } else if (frame.len as u32) < _CANXL_MIN_DLEN || frame.len as u32 > _CANXL_MAX_DLEN {
+            Err(ConstructionError::TooMuchData)
+        } else {
+            Ok(Self(frame))
+        }
+    }
+}
+
+// ===== FrameBuilder =====
+
+/// A builder for classic CAN 2.0 frames.
+///
+/// The ID is given as a raw integer. It is treated as a standard ID unless
+/// `extended(true)` is set, so an extended ID <= 0x7FF can be requested
+/// explicitly.
+///
+/// Calling `fd()` switches to an [`FdFrameBuilder`] to create an FD frame.
+///
+/// # Examples
+///
+/// ```
+/// # use kernel::socketcan_embedded::Frame;
+/// # use kernel::socketcan_error::ConstructionError;
+/// # use kernel::socketcan_frame::FrameBuilder;
+/// let frame = FrameBuilder::new(0x100)
+///     .extended(true)
+///     .data(&[1, 2, 3])
+///     .build()
+///     .unwrap();
+/// assert!(frame.is_extended());
+/// assert_eq!(frame.data(), &[1, 2, 3]);
+///
+/// let remote = FrameBuilder::new(0x123).rtr(true).dlc(4).build_remote().unwrap();
+/// assert!(remote.is_remote_frame());
+/// assert_eq!(remote.requested_dlc(), 4);
+///
+/// let err = FrameBuilder::new(0x123).rtr(true).data(&[1]).build_remote();
+/// assert_eq!(err.err(), Some(ConstructionError::WrongFrameType));
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct FrameBuilder<'a> {
+    id: u32,
+    extended: bool,
+    rtr: bool,
+    dlc: Option<usize>,
+    data: &'a [u8],
+}
+
+impl<'a> FrameBuilder<'a> {
+    /// Starts building a frame with the specified raw ID.
+    pub fn new(id: u32) -> Self {
+        Self {
+            id,
+            extended: false,
+            rtr: false,
+            dlc: None,
+            data: &[],
+        }
+    }
+
+    /// Whether the ID is a 29-bit extended ID.
+    pub fn extended(mut self, on: bool) -> Self {
+        self.extended = on;
+        self
+    }
+
+    /// Whether to create a remote transmission request frame.
+    ///
+    /// A remote frame is created with `build_remote()`. Setting this makes
+    /// `build()` fail, since it creates a data frame.
+    pub fn rtr(mut self, on: bool) -> Self {
+        self.rtr = on;
+        self
+    }
+
+    /// Sets the data length of the frame.
+    ///
+    /// This is the number of bytes requested by a remote frame. For a data
+    /// frame, the length comes from the data, so if this is set it must
+    /// agree with it.
+    pub fn dlc(mut self, dlc: usize) -> Self {
+        self.dlc = Some(dlc);
+        self
+    }
+
+    /// Sets the data payload of the frame.
+    pub fn data(mut self, data: &'a [u8]) -> Self {
+        self.data = data;
+        self
+    }
+
+    /// Switches to building an FD frame.
+    pub fn fd(self) -> FdFrameBuilder<'a> {
+        FdFrameBuilder {
+            base: self,
+            brs: false,
+        }
+    }
+
+    /// Validates the ID and gets it as a composite SocketCAN ID word.
+    fn can_id(&self) -> Result<canid_t, ConstructionError> {
+        let id: Id = if self.extended {
+            ExtendedId::new(self.id)
+                .ok_or(ConstructionError::IDTooLarge)?
+                .into()
+        } else {
+            u16::try_from(self.id)
+                .ok()
+                .and_then(StandardId::new)
+                .ok_or(ConstructionError::IDTooLarge)?
+                .into()
+        };
+        Ok(id_to_canid_t(id))
+    }
+
+    /// Creates a data frame.
+    ///
+    /// It fails if the ID is out of range, the data is too long, or if
+    /// `rtr(true)` was set. If `dlc()` was set, it fails with
+    /// `ValueOutOfRange` unless it matches the length of the data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_embedded::Frame;
+    /// # use kernel::socketcan_error::ConstructionError;
+    /// # use kernel::socketcan_frame::FrameBuilder;
+    /// let frame = FrameBuilder::new(0x123).dlc(2).data(&[1, 2]).build().unwrap();
+    /// assert_eq!(frame.dlc(), 2);
+    ///
+    /// let err = FrameBuilder::new(0x123).dlc(4).data(&[1, 2]).build();
+    /// assert_eq!(err.err(), Some(ConstructionError::ValueOutOfRange));
+    /// ```
+    pub fn build(self) -> Result<CanDataFrame, ConstructionError> {
+        if self.rtr {
+            return Err(ConstructionError::WrongFrameType);
+        }
+        if self.dlc.map_or(false, |dlc| dlc != self.data.len()) {
+            return Err(ConstructionError::ValueOutOfRange);
+        }
+        let can_id = self.can_id()?;
+        CanDataFrame::init(can_id, self.data)
+    }
+
+    /// Creates a remote frame, requesting the number of bytes set with
+    /// `dlc()`, or none if it wasn't set.
+    ///
+    /// It fails if the ID is out of range, the requested length is more
+    /// than 8, or if data was supplied, since a remote frame carries none.
+    pub fn build_remote(self) -> Result<CanRemoteFrame, ConstructionError> {
+        if !self.data.is_empty() {
+            return Err(ConstructionError::WrongFrameType);
+        }
+        let can_id = self.can_id()?;
+        CanRemoteFrame::init(can_id, self.dlc.unwrap_or(0))
+    }
+}
+
+/// A builder for CAN FD frames.
+///
+/// This is created from [`FrameBuilder::fd()`].
+#[allow(missing_debug_implementations)]
+pub struct FdFrameBuilder<'a> {
+    base: FrameBuilder<'a>,
+    brs: bool,
+}
+
+impl<'a> FdFrameBuilder<'a> {
+    /// Whether to use a bit rate switch for the data phase.
+    pub fn brs(mut self, on: bool) -> Self {
+        self.brs = on;
+        self
+    }
+
+    /// Sets the data payload of the frame.
+    pub fn data(mut self, data: &'a [u8]) -> Self {
+        self.base.data = data;
+        self
+    }
+
+    /// Creates the FD frame.
+    ///
+    /// It fails if the ID is out of range or the data is too long.
+    /// FD frames do not support remote requests, so this is also an error
+    /// if `rtr(true)` was set. As with a classic frame, a `dlc()` that
+    /// doesn't match the length of the data is rejected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_frame::{Frame, FrameBuilder};
+    /// let frame = FrameBuilder::new(0x123).fd().brs(true).data(&[0; 12]).build().unwrap();
+    /// assert!(frame.is_brs());
+    /// assert_eq!(frame.len(), 12);
+    /// ```
+    pub fn build(self) -> Result<CanFdFrame, ConstructionError> {
+        if self.base.rtr {
+            return Err(ConstructionError::WrongFrameType);
+        }
+        if self.base.dlc.map_or(false, |dlc| dlc != self.base.data.len()) {
+            return Err(ConstructionError::ValueOutOfRange);
+        }
+        let can_id = self.base.can_id()?;
+        let flags = if self.brs { _CANFD_BRS } else { 0 };
+        CanFdFrame::init(can_id, self.base.data, FdFlags::new(flags))
+    }
+}
+
+// ===== TimestampedFrame =====
+
+/// A frame paired with the time at which it was captured.
+///
+/// The timestamp is a [`Duration`](core::time::Duration) from whatever
+/// reference clock the caller uses, such as the kernel monotonic clock.
+/// The wrapper dereferences to the inner frame, so all of the frame's
+/// methods remain available.
+///
+/// # Examples
+///
+/// ```
+/// # use core::time::Duration;
+/// # use kernel::socketcan_embedded::Frame;
+/// # use kernel::socketcan_frame::{CanDataFrame, TimestampedFrame};
+/// # use kernel::socketcan_id::StandardId;
+/// let id = StandardId::new(0x100).unwrap();
+/// let request = CanDataFrame::new(id, &[1]).unwrap();
+/// let reply = CanDataFrame::new(id, &[1, 2]).unwrap();
+///
+/// let sent = TimestampedFrame::new(request, Duration::from_micros(1_000));
+/// let received = TimestampedFrame::new(reply, Duration::from_micros(1_250));
+///
+/// assert_eq!(received.since(&sent), Some(Duration::from_micros(250)));
+/// assert_eq!(sent.since(&received), None);
+///
+/// // The frame's own methods are available through the wrapper
+/// assert_eq!(received.data(), &[1, 2]);
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct TimestampedFrame<F> {
+    frame: F,
+    timestamp: core::time::Duration,
+}
+
+impl<F> TimestampedFrame<F> {
+    /// Pairs a frame with its capture time.
+    pub fn new(frame: F, timestamp: core::time::Duration) -> Self {
+        Self { frame, timestamp }
+    }
+
+    /// Gets the time at which the frame was captured.
+    pub fn timestamp(&self) -> core::time::Duration {
+        self.timestamp
+    }
+
+    /// Gets a reference to the inner frame.
+    pub fn frame(&self) -> &F {
+        &self.frame
+    }
+
+    /// Unwraps the inner frame, discarding the timestamp.
+    pub fn into_inner(self) -> F {
+        self.frame
+    }
+
+    /// Gets the time elapsed between an earlier frame and this one.
+    ///
+    /// Returns `None` if the other frame was captured after this one.
+    pub fn since<G>(&self, earlier: &TimestampedFrame<G>) -> Option<core::time::Duration> {
+        self.timestamp.checked_sub(earlier.timestamp)
+    }
+}
+
+impl<F> core::ops::Deref for TimestampedFrame<F> {
+    type Target = F;
+
+    fn deref(&self) -> &F {
+        &self.frame
+    }
+}
+
+impl<F> core::ops::DerefMut for TimestampedFrame<F> {
+    fn deref_mut(&mut self) -> &mut F {
+        &mut self.frame
+    }
 }
@@ -89,6 +89,44 @@ impl ExtendedId {
         // ID-28 to ID-18
         StandardId((self.0 >> 18) as u16)
     }
+
+    /// Returns the 18-bit extension part of this extended identifier.
+    ///
+    /// On the bus, an extended ID is sent as the 11-bit Base ID (see
+    /// `standard_id()`), followed by these lower 18 bits. Arbitration, and
+    /// the `Ord` implementation for [`Id`], compare the IDs in that order.
+    #[must_use]
+    pub fn sub_id(&self) -> u32 {
+        // ID-17 to ID-0
+        self.0 & ((1 << 18) - 1)
+    }
+
+    /// Creates an extended identifier from its Base ID and 18-bit
+    /// extension.
+    ///
+    /// This will return `None` if `sub` is out of range of an 18-bit
+    /// integer (`> 0x3FFFF`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_id::{ExtendedId, StandardId};
+    /// let base = StandardId::new(0x123).unwrap();
+    /// let id = ExtendedId::from_parts(base, 0x2_ABCD).unwrap();
+    /// assert_eq!(id.as_raw(), (0x123 << 18) | 0x2_ABCD);
+    /// assert_eq!(id.standard_id(), base);
+    /// assert_eq!(id.sub_id(), 0x2_ABCD);
+    ///
+    /// assert!(ExtendedId::from_parts(base, 0x4_0000).is_none());
+    /// ```
+    #[must_use]
+    pub fn from_parts(base: StandardId, sub: u32) -> Option<Self> {
+        if sub < (1 << 18) {
+            Some(Self(((base.0 as u32) << 18) | sub))
+        } else {
+            None
+        }
+    }
 }
 
 /// A CAN Identifier (standard or extended).
@@ -106,11 +144,7 @@ impl Ord for Id {
         let split_id = |id: &Id| {
             let (standard_id_part, ide_bit, extended_id_part) = match id {
                 Id::Standard(StandardId(x)) => (*x, 0, 0),
-                Id::Extended(x) => (
-                    x.standard_id().0,
-                    1,
-                    x.0 & ((1 << 18) - 1), // Bit ID-17 to ID-0
-                ),
+                Id::Extended(x) => (x.standard_id().0, 1, x.sub_id()),
             };
             (standard_id_part, ide_bit, extended_id_part)
         };
@@ -125,6 +159,20 @@ impl PartialOrd for Id {
     }
 }
 
+impl From<StandardId> for u16 {
+    #[inline]
+    fn from(id: StandardId) -> Self {
+        id.as_raw()
+    }
+}
+
+impl From<ExtendedId> for u32 {
+    #[inline]
+    fn from(id: ExtendedId) -> Self {
+        id.as_raw()
+    }
+}
+
 impl From<StandardId> for Id {
     #[inline]
     fn from(id: StandardId) -> Self {
@@ -0,0 +1,155 @@
+// Implements J1939 addressing for extended CAN identifiers.
+//
+// This file is part of the Rust 'socketcan-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+
+//! SAE J1939 addressing.
+//!
+//! J1939 uses the 29-bit extended CAN ID to carry addressing information:
+//!
+//! ```text
+//! Bits 28-26  Priority
+//! Bit  25     Extended data page (EDP)
+//! Bit  24     Data page (DP)
+//! Bits 23-16  PDU format (PF)
+//! Bits 15-8   PDU specific (PS)
+//! Bits 7-0    Source address (SA)
+//! ```
+//!
+//! When the PDU format is less than 240 (PDU1), the message is addressed
+//! to a specific node and the PDU specific byte is the destination address.
+//! Otherwise (PDU2) the message is broadcast and the PDU specific byte is
+//! part of the parameter group number (PGN).
+//!
+//! This only covers the addressing, not the J1939 transport protocol.
+
+use crate::socketcan_id::ExtendedId;
+
+/// The lowest PDU format value for a broadcast (PDU2) message.
+pub const J1939_PDU2_MIN: u8 = 240;
+
+/// The global (broadcast) destination address.
+pub const J1939_GLOBAL_ADDR: u8 = 0xFF;
+
+/// The J1939 addressing fields of an extended CAN ID.
+///
+/// # Examples
+///
+/// ```
+/// # use kernel::socketcan_id::ExtendedId;
+/// // EEC1 (engine speed) from the engine, a broadcast PDU2 message
+/// let eec1 = ExtendedId::new(0x0CF0_0400).unwrap();
+/// assert_eq!(eec1.j1939_pgn(), 61444);
+///
+/// let j1939 = eec1.j1939();
+/// assert_eq!(j1939.priority, 3);
+/// assert_eq!(j1939.source_address, 0x00);
+/// assert_eq!(j1939.destination_address, None);
+/// assert_eq!(j1939.to_extended_id(), Some(eec1));
+///
+/// // A request from address 0xF9 to address 0x00, a PDU1 message
+/// let request = ExtendedId::new(0x18EA_00F9).unwrap().j1939();
+/// assert_eq!(request.pgn, 0xEA00);
+/// assert_eq!(request.source_address, 0xF9);
+/// assert_eq!(request.destination_address, Some(0x00));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct J1939Id {
+    /// The message priority (0-7), where 0 is the highest
+    pub priority: u8,
+    /// The 18-bit parameter group number.
+    /// For a PDU1 message the lower (PDU specific) byte is zero.
+    pub pgn: u32,
+    /// The address of the node that sent the message
+    pub source_address: u8,
+    /// The address of the destination node for a PDU1 message, or `None`
+    /// for a broadcast PDU2 message
+    pub destination_address: Option<u8>,
+}
+
+impl J1939Id {
+    /// Gets the PDU format byte of the PGN.
+    pub fn pdu_format(&self) -> u8 {
+        (self.pgn >> 8) as u8
+    }
+
+    /// Gets the PDU specific byte: the destination address for a PDU1
+    /// message, or the group extension for a PDU2 message.
+    ///
+    /// A PDU1 message without a destination address is sent to the global
+    /// address, as in [`J1939Id::to_extended_id()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use kernel::socketcan_j1939::{J1939Id, J1939_GLOBAL_ADDR};
+    /// let mut request = J1939Id {
+    ///     priority: 6,
+    ///     pgn: 0xEA00,
+    ///     source_address: 0xF9,
+    ///     destination_address: Some(0x00),
+    /// };
+    /// assert_eq!(request.pdu_specific(), 0x00);
+    ///
+    /// request.destination_address = None;
+    /// assert_eq!(request.pdu_specific(), J1939_GLOBAL_ADDR);
+    /// ```
+    pub fn pdu_specific(&self) -> u8 {
+        if self.pdu_format() < J1939_PDU2_MIN {
+            self.destination_address.unwrap_or(J1939_GLOBAL_ADDR)
+        } else {
+            self.pgn as u8
+        }
+    }
+
+    /// Converts the addressing fields back into an extended CAN ID.
+    ///
+    /// A PDU1 message without a destination address is sent to the global
+    /// address. This returns `None` if the priority is more than 7 or the
+    /// PGN is more than 18 bits.
+    pub fn to_extended_id(&self) -> Option<ExtendedId> {
+        if self.priority > 7 || self.pgn > 0x3_FFFF {
+            return None;
+        }
+        let raw = (self.priority as u32) << 26
+            | (self.pgn & 0x3_FF00) << 8
+            | (self.pdu_specific() as u32) << 8
+            | self.source_address as u32;
+        ExtendedId::new(raw)
+    }
+}
+
+impl From<ExtendedId> for J1939Id {
+    fn from(id: ExtendedId) -> Self {
+        let raw = id.as_raw();
+        let pf = (raw >> 16) as u8;
+        let ps = (raw >> 8) as u8;
+        let (pgn, destination_address) = if pf < J1939_PDU2_MIN {
+            ((raw >> 8) & 0x3_FF00, Some(ps))
+        } else {
+            ((raw >> 8) & 0x3_FFFF, None)
+        };
+        Self {
+            priority: ((raw >> 26) & 0x07) as u8,
+            pgn,
+            source_address: raw as u8,
+            destination_address,
+        }
+    }
+}
+
+impl ExtendedId {
+    /// Gets the J1939 parameter group number (PGN) of this ID.
+    pub fn j1939_pgn(&self) -> u32 {
+        J1939Id::from(*self).pgn
+    }
+
+    /// Gets the J1939 addressing fields of this ID.
+    pub fn j1939(&self) -> J1939Id {
+        J1939Id::from(*self)
+    }
+}
@@ -0,0 +1,186 @@
+// Implements a bus load meter for CAN frames.
+//
+// This file is part of the Rust 'socketcan-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+
+//! CAN bus load measurement.
+//!
+//! The [`BusLoadMeter`] estimates how much of the bus time is used by
+//! traffic, from the timestamps and bit lengths of the frames that are
+//! seen. The bit lengths are the worst-case counts from
+//! [`classic_bit_length()`](crate::socketcan_frame::classic_bit_length)
+//! and [`fd_bit_length()`](crate::socketcan_frame::fd_bit_length), so the
+//! load is an upper bound.
+
+use crate::error::Result;
+use crate::socketcan_frame::{CanFdFrame, CanFrame};
+use alloc::vec::Vec;
+use core::time::Duration;
+
+/// A load of 100%, in hundredths of a percent.
+pub const FULL_LOAD: u32 = 10_000;
+
+/// Measures the bus load from a stream of timestamped frames.
+///
+/// The timestamps are a [`Duration`] from any reference clock, and must be
+/// recorded in increasing order. Loads are reported as integers, in
+/// hundredths of a percent, since floating point can't be used in the
+/// kernel.
+///
+/// # Examples
+///
+/// ```
+/// # use core::time::Duration;
+/// # use kernel::socketcan_embedded::Frame;
+/// # use kernel::socketcan_frame::{CanDataFrame, CanFrame};
+/// # use kernel::socketcan_id::StandardId;
+/// # use kernel::socketcan_load::BusLoadMeter;
+/// let id = StandardId::new(0x100).unwrap();
+/// let frame = CanFrame::Data(CanDataFrame::new(id, &[0; 8]).unwrap());
+///
+/// // A 135-bit frame every millisecond at 500 kbit/s is 270 us of every
+/// // 1000 us, or a 27% load.
+/// let mut meter = BusLoadMeter::new(500_000);
+/// for i in 0..2000 {
+///     meter.record(Duration::from_millis(i), &frame).unwrap();
+/// }
+/// assert!((2690..=2710).contains(&meter.load()));
+/// assert_eq!(meter.instantaneous_load(), 2700);
+/// ```
+#[derive(Debug)]
+pub struct BusLoadMeter {
+    bitrate: u32,
+    data_bitrate: u32,
+    window: Duration,
+    /// The timestamp and bus time of each frame in the window.
+    samples: Vec<(Duration, Duration)>,
+}
+
+impl BusLoadMeter {
+    /// Creates a meter for a bus with the specified nominal bit rate, in
+    /// bits per second.
+    ///
+    /// The load is averaged over a one second window, and FD frames use
+    /// the nominal bit rate for their data phase, unless set otherwise.
+    pub fn new(bitrate: u32) -> Self {
+        Self {
+            bitrate,
+            data_bitrate: bitrate,
+            window: Duration::from_secs(1),
+            samples: Vec::new(),
+        }
+    }
+
+    /// Sets the bit rate for the data phase of FD frames that use a bit
+    /// rate switch.
+    pub fn with_data_bitrate(mut self, data_bitrate: u32) -> Self {
+        self.data_bitrate = data_bitrate;
+        self
+    }
+
+    /// Sets the time window over which the load is averaged.
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Gets the time to send the specified number of bits at a bit rate.
+    fn bus_time(bits: usize, bitrate: u32) -> Duration {
+        if bitrate == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_nanos(bits as u64 * 1_000_000_000 / bitrate as u64)
+    }
+
+    /// Records a frame that was seen on the bus, given its bit counts at
+    /// the nominal and data bit rates.
+    pub fn record_bits(&mut self, timestamp: Duration, nominal: usize, data: usize) -> Result {
+        let time = Self::bus_time(nominal, self.bitrate) + Self::bus_time(data, self.data_bitrate);
+        self.samples.try_push((timestamp, time))?;
+
+        let start = timestamp.saturating_sub(self.window);
+        let n = self.samples.partition_point(|(ts, _)| *ts < start);
+        self.samples.drain(..n);
+        Ok(())
+    }
+
+    /// Records a frame that was seen on the bus.
+    ///
+    /// Error frames are reports from the controller rather than traffic,
+    /// so they are ignored.
+    pub fn record(&mut self, timestamp: Duration, frame: &CanFrame) -> Result {
+        let bits = match frame {
+            CanFrame::Data(frame) => frame.bit_length(),
+            CanFrame::Remote(frame) => frame.bit_length(),
+            CanFrame::Error(_) => return Ok(()),
+            CanFrame::Fd(frame) => return self.record_fd(timestamp, frame),
+        };
+        self.record_bits(timestamp, bits, 0)
+    }
+
+    /// Records an FD frame that was seen on the bus.
+    pub fn record_fd(&mut self, timestamp: Duration, frame: &CanFdFrame) -> Result {
+        let (nominal, data) = frame.bit_length();
+        self.record_bits(timestamp, nominal, data)
+    }
+
+    /// Gets a load as a ratio of bus time, in hundredths of a percent,
+    /// capped at 100%.
+    fn ratio(busy: Duration, total: Duration) -> u32 {
+        if total.is_zero() {
+            return if busy.is_zero() { 0 } else { FULL_LOAD };
+        }
+        // Kept in 64 bits, since the kernel lacks 128-bit division.
+        let total = Self::nanos(total);
+        let busy = Self::nanos(busy).min(total);
+        let load = match busy.checked_mul(FULL_LOAD as u64) {
+            Some(n) => n / total,
+            // Too long to scale in nanoseconds, so scale the total down
+            // instead. It is at least `busy`, so this can't be zero.
+            None => busy / (total / FULL_LOAD as u64),
+        };
+        load.min(FULL_LOAD as u64) as u32
+    }
+
+    /// Gets a duration in nanoseconds, saturating at `u64::MAX`.
+    fn nanos(d: Duration) -> u64 {
+        d.as_secs()
+            .checked_mul(1_000_000_000)
+            .and_then(|n| n.checked_add(d.subsec_nanos() as u64))
+            .unwrap_or(u64::MAX)
+    }
+
+    /// Gets the load over the current window, in hundredths of a percent.
+    ///
+    /// This is the bus time used by the frames recorded in the window
+    /// that ends with the most recent frame. A value of 2500 is a 25% load.
+    pub fn load(&self) -> u32 {
+        if self.window.is_zero() {
+            return 0;
+        }
+        let busy: Duration = self.samples.iter().map(|(_, time)| *time).sum();
+        Self::ratio(busy, self.window)
+    }
+
+    /// Gets the instantaneous load, in hundredths of a percent.
+    ///
+    /// This is the bus time of the most recent frame, relative to the time
+    /// since the frame before it. It is zero until two frames have been
+    /// recorded.
+    pub fn instantaneous_load(&self) -> u32 {
+        match self.samples.as_slice() {
+            [.., (prev, _), (last, time)] => Self::ratio(*time, last.saturating_sub(*prev)),
+            _ => 0,
+        }
+    }
+
+    /// Clears all the recorded frames.
+    pub fn reset(&mut self) {
+        self.samples.clear();
+    }
+}
+
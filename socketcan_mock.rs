@@ -0,0 +1,264 @@
+// Implements an in-memory CAN interface for testing.
+//
+// This file is part of the Rust 'socketcan-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+
+//! Mock CAN interfaces.
+//!
+//! [`MockCan`] implements the blocking [`Can`] and non-blocking [`NbCan`]
+//! traits entirely in memory, so that application logic written against
+//! those traits can be exercised without any hardware or virtual bus.
+//!
+//! Transmitted frames are collected in an outbox, which the test drains
+//! with [`MockCan::pop_tx()`], while the frames to be received are queued
+//! up front with [`MockCan::push_rx()`].
+
+use crate::socketcan_embedded::{Can, Error, ErrorKind, Frame, NbCan};
+use crate::socketcan_frame::CanFrame;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// An error from a mock CAN interface.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MockCanError {
+    /// There are no frames waiting to be received.
+    WouldBlock,
+    /// A transmitted frame could not be queued.
+    Transmit,
+}
+
+impl Error for MockCanError {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+}
+
+impl fmt::Display for MockCanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use MockCanError::*;
+        let msg = match *self {
+            WouldBlock => "no frames to receive",
+            Transmit => "unable to queue the transmitted frame",
+        };
+        write!(f, "{}", msg)
+    }
+}
+
+/// An in-memory CAN interface.
+///
+/// Frames that are transmitted are copied into an outbox, and frames are
+/// received, in order, from an inbox that is filled by the test. A receive
+/// with an empty inbox fails with [`MockCanError::WouldBlock`] rather than
+/// blocking.
+///
+/// Transmitted frames are cloned into the outbox unchanged, so the kind of
+/// frame, its flags, and its length are all preserved.
+///
+/// # Examples
+///
+/// ```
+/// # use kernel::socketcan_embedded::Can;
+/// # use kernel::socketcan_error::CanError;
+/// # use kernel::socketcan_frame::{CanErrorFrame, CanFrame, Frame};
+/// # use kernel::socketcan_mock::MockCan;
+/// let mut can = MockCan::<CanFrame>::new();
+///
+/// let frame = CanFrame::Error(CanErrorFrame::from(CanError::BusOff));
+/// Can::transmit(&mut can, &frame).unwrap();
+///
+/// let sent = can.pop_tx().unwrap();
+/// assert!(matches!(sent, CanFrame::Error(_)));
+/// assert_eq!(sent.id_word(), frame.id_word());
+/// assert!(can.pop_tx().is_none());
+///
+/// can.push_rx(frame).unwrap();
+/// assert!(Can::receive(&mut can).is_ok());
+/// assert!(Can::receive(&mut can).is_err());
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct MockCan<F: Frame + Clone = CanFrame> {
+    pub(crate) inbox: Vec<F>,
+    pub(crate) outbox: Vec<F>,
+}
+
+impl<F: Frame + Clone> Default for MockCan<F> {
+    fn default() -> Self {
+        Self {
+            inbox: Vec::new(),
+            outbox: Vec::new(),
+        }
+    }
+}
+
+impl<F: Frame + Clone> MockCan<F> {
+    /// Creates a mock interface with nothing to receive.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues a frame to be received by the interface.
+    pub fn push_rx(&mut self, frame: F) -> crate::error::Result {
+        self.inbox.try_push(frame)?;
+        Ok(())
+    }
+
+    /// Takes the oldest frame that was transmitted by the interface.
+    pub fn pop_tx(&mut self) -> Option<F> {
+        if self.outbox.is_empty() {
+            None
+        } else {
+            Some(self.outbox.remove(0))
+        }
+    }
+
+    /// The number of frames waiting to be received.
+    pub fn rx_pending(&self) -> usize {
+        self.inbox.len()
+    }
+
+    /// The number of transmitted frames that have not been taken.
+    pub fn tx_pending(&self) -> usize {
+        self.outbox.len()
+    }
+
+    fn queue_tx(&mut self, frame: &F) -> Result<(), MockCanError> {
+        self.outbox
+            .try_push(frame.clone())
+            .map_err(|_| MockCanError::Transmit)
+    }
+
+    fn dequeue_rx(&mut self) -> Result<F, MockCanError> {
+        if self.inbox.is_empty() {
+            Err(MockCanError::WouldBlock)
+        } else {
+            Ok(self.inbox.remove(0))
+        }
+    }
+}
+
+impl<F: Frame + Clone> Can for MockCan<F> {
+    type Frame = F;
+    type Error = MockCanError;
+
+    /// Copies the frame into the outbox.
+    fn transmit(&mut self, frame: &Self::Frame) -> Result<(), Self::Error> {
+        self.queue_tx(frame)
+    }
+
+    /// Takes the next frame from the inbox.
+    fn receive(&mut self) -> Result<Self::Frame, Self::Error> {
+        self.dequeue_rx()
+    }
+}
+
+impl<F: Frame + Clone> NbCan for MockCan<F> {
+    type Frame = F;
+    type Error = MockCanError;
+
+    /// Copies the frame into the outbox.
+    /// The outbox is never full, so no frame is ever replaced.
+    fn transmit(&mut self, frame: &Self::Frame) -> Result<Option<Self::Frame>, Self::Error> {
+        self.queue_tx(frame).map(|_| None)
+    }
+
+    /// Takes the next frame from the inbox.
+    fn receive(&mut self) -> Result<Self::Frame, Self::Error> {
+        self.dequeue_rx()
+    }
+}
+
+/// Two mock interfaces connected to the same in-memory bus.
+///
+/// Frames transmitted by one node are delivered to the other by
+/// [`CanPair::deliver()`]. Each node sends its frames in the order they
+/// were transmitted, and when both nodes have a frame pending, the one
+/// with the lower [`Id`](crate::socketcan_id::Id) wins arbitration and
+/// is delivered first. On a tie, node `a` wins.
+///
+/// # Examples
+///
+/// ```
+/// # use kernel::socketcan_embedded::{Can, Frame};
+/// # use kernel::socketcan_frame::CanDataFrame;
+/// # use kernel::socketcan_id::StandardId;
+/// # use kernel::socketcan_mock::loopback_pair;
+/// let id = |raw| StandardId::new(raw).unwrap();
+/// let mut bus = loopback_pair::<CanDataFrame>();
+///
+/// // Node A sends a request
+/// let request = CanDataFrame::new(id(0x700), &[0x01]).unwrap();
+/// bus.a.transmit(&request).unwrap();
+/// assert_eq!(bus.deliver().unwrap(), 1);
+///
+/// // Node B receives it and replies
+/// let received = bus.b.receive().unwrap();
+/// assert_eq!(received.data(), &[0x01]);
+/// let reply = CanDataFrame::new(id(0x701), &[0x01, 0xAA]).unwrap();
+/// bus.b.transmit(&reply).unwrap();
+/// assert_eq!(bus.deliver().unwrap(), 1);
+///
+/// let response = bus.a.receive().unwrap();
+/// assert_eq!(response.id(), id(0x701).into());
+/// assert_eq!(response.data(), &[0x01, 0xAA]);
+/// assert_eq!(bus.b.rx_pending(), 0);
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct CanPair<F: Frame + Clone = CanFrame> {
+    /// The first node on the bus.
+    pub a: MockCan<F>,
+    /// The second node on the bus.
+    pub b: MockCan<F>,
+}
+
+/// Creates a pair of connected mock interfaces.
+pub fn loopback_pair<F: Frame + Clone>() -> CanPair<F> {
+    CanPair::new()
+}
+
+impl<F: Frame + Clone> Default for CanPair<F> {
+    fn default() -> Self {
+        Self {
+            a: MockCan::new(),
+            b: MockCan::new(),
+        }
+    }
+}
+
+impl<F: Frame + Clone> CanPair<F> {
+    /// Creates a pair of connected mock interfaces.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Delivers all the pending transmitted frames to the other node,
+    /// in bus arbitration order.
+    ///
+    /// Returns the number of frames that were delivered.
+    pub fn deliver(&mut self) -> crate::error::Result<usize> {
+        let mut n = 0;
+        loop {
+            let from_a = match (self.a.outbox.first(), self.b.outbox.first()) {
+                (None, None) => break,
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (Some(fa), Some(fb)) => fa.id() <= fb.id(),
+            };
+
+            if from_a {
+                self.b.inbox.try_reserve(1)?;
+                let frame = self.a.outbox.remove(0);
+                self.b.inbox.try_push(frame)?;
+            } else {
+                self.a.inbox.try_reserve(1)?;
+                let frame = self.b.outbox.remove(0);
+                self.a.inbox.try_push(frame)?;
+            }
+            n += 1;
+        }
+        Ok(n)
+    }
+}
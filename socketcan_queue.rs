@@ -0,0 +1,116 @@
+// Implements a software transmit queue for CAN frames.
+//
+// This file is part of the Rust 'socketcan-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+
+//! Software priority queue for outgoing CAN frames.
+//!
+//! A gateway that buffers outgoing traffic can use a [`TxQueue`] to send
+//! frames in the order in which they would win arbitration on the bus,
+//! rather than the order in which they were queued.
+
+use crate::error::Result;
+use crate::socketcan_embedded::Can;
+use crate::socketcan_frame::{CanFrame, Frame};
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+/// A queue of frames that pops them in CAN arbitration order.
+///
+/// Frames are ordered with [`Frame::priority_cmp()`]. Frames of equal
+/// priority are popped in the order in which they were pushed.
+///
+/// # Examples
+///
+/// ```
+/// # use kernel::socketcan_embedded::Frame;
+/// # use kernel::socketcan_frame::{CanDataFrame, CanFrame, Frame as _};
+/// # use kernel::socketcan_queue::TxQueue;
+/// let frame = |id: u32, data: &[u8]| -> CanFrame {
+///     match id {
+///         0..=0x7FF => CanDataFrame::new_standard(id as u16, data),
+///         _ => CanDataFrame::new_extended(id, data),
+///     }
+///     .unwrap()
+///     .into()
+/// };
+///
+/// let mut queue = TxQueue::new();
+/// queue.push(frame(0x1000, &[])).unwrap();
+/// queue.push(frame(0x200, &[1])).unwrap();
+/// queue.push(frame(0x100, &[])).unwrap();
+/// queue.push(frame(0x200, &[2])).unwrap();
+///
+/// let mut order = Vec::new();
+/// while let Some(f) = queue.pop_highest_priority() {
+///     order.push((f.id_word() & 0x1FFF_FFFF, f.data().first().copied()));
+/// }
+/// assert_eq!(order, [
+///     (0x100, None),
+///     (0x200, Some(1)),
+///     (0x200, Some(2)),
+///     (0x1000, None),
+/// ]);
+/// ```
+#[allow(missing_debug_implementations)]
+#[derive(Default)]
+pub struct TxQueue {
+    // Kept sorted from lowest to highest priority, so the next frame to
+    // send is always at the end.
+    frames: Vec<CanFrame>,
+}
+
+impl TxQueue {
+    /// Creates an empty queue.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of frames in the queue.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Whether the queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Adds a frame to the queue.
+    ///
+    /// This fails only if memory for the frame could not be allocated.
+    pub fn push(&mut self, frame: CanFrame) -> Result {
+        // Goes in front of every frame with the same priority, so those
+        // that were queued earlier still leave first.
+        let pos = self
+            .frames
+            .partition_point(|f| f.priority_cmp(&frame) == Ordering::Greater);
+        self.frames.try_push(frame)?;
+        self.frames[pos..].rotate_right(1);
+        Ok(())
+    }
+
+    /// Removes and returns the frame that would win arbitration.
+    pub fn pop_highest_priority(&mut self) -> Option<CanFrame> {
+        self.frames.pop()
+    }
+
+    /// Transmits all the queued frames, in priority order, to a CAN
+    /// interface.
+    ///
+    /// If a transmit fails, the frame that failed is dropped, the remaining
+    /// frames are left in the queue, and the error is returned.
+    pub fn drain_into<C>(&mut self, can: &mut C) -> core::result::Result<(), C::Error>
+    where
+        C: Can<Frame = CanFrame>,
+    {
+        while let Some(frame) = self.pop_highest_priority() {
+            can.transmit(&frame)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+// Implements dispatching of CAN frames to handlers by ID.
+//
+// This file is part of the Rust 'socketcan-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+
+//! CAN frame routing.
+//!
+//! A [`FrameRouter`] holds a list of handlers, each registered with an ID
+//! and mask, and passes every frame it is given to all of the handlers
+//! that match it.
+
+use crate::error::Result;
+use crate::socketcan_filter::CanFilter;
+use crate::socketcan_frame::*;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+/// A handler for frames that were routed to it.
+type Handler<'a> = Box<dyn FnMut(&CanFrame) + 'a>;
+
+/// Dispatches frames to handlers that are keyed by ID.
+///
+/// Each handler is registered with an ID and mask, which are matched
+/// against the frame's ID word in the same way as a [`CanFilter`]. When
+/// the matches overlap, every matching handler is called, in the order in
+/// which they were registered.
+///
+/// # Examples
+///
+/// ```
+/// # use core::cell::Cell;
+/// # use kernel::socketcan_embedded::Frame;
+/// # use kernel::socketcan_frame::{CanDataFrame, CanFrame, _CAN_EFF_FLAG};
+/// # use kernel::socketcan_id::StandardId;
+/// # use kernel::socketcan_router::FrameRouter;
+/// let (exact, block) = (Cell::new(0), Cell::new(0));
+///
+/// let mut router = FrameRouter::new();
+/// router.register(0x123, 0x7FF | _CAN_EFF_FLAG, |_| exact.set(exact.get() + 1)).unwrap();
+/// router.register(0x100, 0x700 | _CAN_EFF_FLAG, |_| block.set(block.get() + 1)).unwrap();
+///
+/// let frame = |raw| CanFrame::Data(CanDataFrame::new(StandardId::new(raw).unwrap(), &[]).unwrap());
+/// assert_eq!(router.dispatch(&frame(0x123)), 2);
+/// assert_eq!(router.dispatch(&frame(0x1FF)), 1);
+/// assert_eq!(router.dispatch(&frame(0x200)), 0);
+/// drop(router);
+///
+/// assert_eq!((exact.get(), block.get()), (1, 2));
+/// ```
+#[allow(missing_debug_implementations)]
+#[derive(Default)]
+pub struct FrameRouter<'a> {
+    routes: Vec<(CanFilter, Handler<'a>)>,
+}
+
+impl<'a> FrameRouter<'a> {
+    /// Creates a router with no handlers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a handler for frames with an ID word that matches `id`
+    /// in the bits that are set in `mask`.
+    ///
+    /// As with a [`CanFilter`], the mask should include the EFF flag to
+    /// tell standard and extended IDs apart.
+    pub fn register<F>(&mut self, id: canid_t, mask: canid_t, handler: F) -> Result
+    where
+        F: FnMut(&CanFrame) + 'a,
+    {
+        let handler: Handler<'a> = Box::try_new(handler)?;
+        self.routes.try_push((CanFilter::new(id, mask), handler))?;
+        Ok(())
+    }
+
+    /// Gets the number of registered handlers.
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+
+    /// Whether there are no registered handlers.
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    /// Passes a frame to all of the handlers that match it.
+    ///
+    /// Returns the number of handlers that were called.
+    pub fn dispatch(&mut self, frame: &CanFrame) -> usize {
+        let can_id = frame.id_word();
+        let mut n = 0;
+        for (filter, handler) in self.routes.iter_mut() {
+            if filter.matches(can_id) {
+                handler(frame);
+                n += 1;
+            }
+        }
+        n
+    }
+}
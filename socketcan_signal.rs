@@ -0,0 +1,206 @@
+// Implements signal decoding and encoding for CAN frame payloads.
+//
+// This file is part of the Rust 'socketcan-rs' library.
+//
+// Licensed under the MIT license:
+//   <LICENSE or http://opensource.org/licenses/MIT>
+// This file may not be copied, modified, or distributed except according
+// to those terms.
+
+//! CAN signals.
+//!
+//! A signal is a bit field packed into the data payload of a frame, as
+//! described by a DBC file. The bit numbering follows the DBC conventions:
+//! - Little endian (Intel) signals start at their least significant bit
+//!   and count up through the payload.
+//! - Big endian (Motorola) signals start at their most significant bit and
+//!   count down within a byte, then continue at the top of the next byte.
+//!
+//! In both cases bit `n` is bit `n % 8` of byte `n / 8`.
+
+use crate::socketcan_embedded::Frame;
+use crate::socketcan_error::ConstructionError;
+use crate::socketcan_frame::CanDataFrame;
+
+/// Gets the position of the next bit of a big endian signal.
+fn next_be_bit(pos: usize) -> usize {
+    if pos % 8 == 0 {
+        pos + 15
+    } else {
+        pos - 1
+    }
+}
+
+/// Extracts an unsigned bit field from a payload.
+///
+/// This returns `None` if the field is longer than 64 bits or extends past
+/// the end of `data`.
+pub fn extract_bits(data: &[u8], start_bit: usize, len: usize, little_endian: bool) -> Option<u64> {
+    if len == 0 || len > 64 {
+        return None;
+    }
+    let mut val = 0u64;
+    let mut pos = start_bit;
+    for i in 0..len {
+        let bit = (data.get(pos / 8)? >> (pos % 8)) & 1;
+        if little_endian {
+            val |= (bit as u64) << i;
+            pos += 1;
+        } else {
+            val = (val << 1) | bit as u64;
+            if i + 1 < len {
+                pos = next_be_bit(pos);
+            }
+        }
+    }
+    Some(val)
+}
+
+/// Inserts an unsigned bit field into a payload.
+///
+/// Only the lower `len` bits of `value` are written. This returns `None`,
+/// without modifying the payload, if the field is longer than 64 bits or
+/// extends past the end of `data`.
+pub fn insert_bits(
+    data: &mut [u8],
+    start_bit: usize,
+    len: usize,
+    little_endian: bool,
+    value: u64,
+) -> Option<()> {
+    // Read it first to validate the range before changing anything.
+    extract_bits(data, start_bit, len, little_endian)?;
+
+    let mut pos = start_bit;
+    for i in 0..len {
+        let shift = if little_endian { i } else { len - 1 - i };
+        let mask = 1u8 << (pos % 8);
+        if (value >> shift) & 1 != 0 {
+            data[pos / 8] |= mask;
+        } else {
+            data[pos / 8] &= !mask;
+        }
+        if little_endian {
+            pos += 1;
+        } else if i + 1 < len {
+            pos = next_be_bit(pos);
+        }
+    }
+    Some(())
+}
+
+/// Divides, rounding to the nearest integer, with halves rounded up.
+///
+/// The divisor must be positive. This returns `None` on overflow.
+fn div_round(n: i64, d: i64) -> Option<i64> {
+    let (q, r) = (n.div_euclid(d), n.rem_euclid(d));
+    if r >= d - r {
+        q.checked_add(1)
+    } else {
+        Some(q)
+    }
+}
+
+/// A scaled signal in the payload of a frame.
+///
+/// Floating point can't be used in the kernel, so physical values are
+/// integers in a unit chosen for the signal, and the scale is the ratio
+/// `factor_num / factor_den`. The physical value of the signal is
+/// `raw * factor_num / factor_den + offset`, where `raw` is the unsigned
+/// bit field in the payload, rounded to the nearest unit.
+///
+/// A DBC signal with a scale of 0.125 and an offset of 0 has a
+/// `factor_num` of 1 and a `factor_den` of 8. Choosing a finer unit, such
+/// as tenths, keeps more of the precision of a fractional scale.
+///
+/// # Examples
+///
+/// ```
+/// # use kernel::socketcan_embedded::Frame;
+/// # use kernel::socketcan_frame::CanDataFrame;
+/// # use kernel::socketcan_id::ExtendedId;
+/// # use kernel::socketcan_signal::Signal;
+/// // The J1939 EEC1 engine speed, at 0.125 rpm per bit
+/// let speed = Signal {
+///     start_bit: 24,
+///     len: 16,
+///     factor_num: 1,
+///     factor_den: 8,
+///     offset: 0,
+///     little_endian: true,
+/// };
+///
+/// let id = ExtendedId::new(0x0CF0_0400).unwrap();
+/// let data = [0xFF, 0xFF, 0xFF, 0x40, 0x1F, 0xFF, 0xFF, 0xFF];
+/// let mut frame = CanDataFrame::new(id, &data).unwrap();
+/// assert_eq!(speed.decode(&frame), Some(1000));
+///
+/// speed.encode(&mut frame, 1500).unwrap();
+/// assert_eq!(&frame.data()[3..5], &[0xE0, 0x2E]);
+/// assert!(speed.encode(&mut frame, -1).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Signal {
+    /// The start bit of the signal, in DBC numbering
+    pub start_bit: usize,
+    /// The length of the signal, in bits (1-64)
+    pub len: usize,
+    /// The numerator of the size of one step of the raw value, in the
+    /// physical unit
+    pub factor_num: i64,
+    /// The denominator of the size of one step of the raw value, which
+    /// must be positive
+    pub factor_den: i64,
+    /// The physical value of a raw value of zero, in the physical unit
+    pub offset: i64,
+    /// Whether the signal is little endian (Intel) rather than big endian
+    /// (Motorola)
+    pub little_endian: bool,
+}
+
+impl Signal {
+    /// Decodes the physical value of the signal from a frame.
+    ///
+    /// This returns `None` if the signal does not fit inside the data of
+    /// the frame, if `factor_den` is not positive, or if the physical value
+    /// does not fit in an `i64`.
+    pub fn decode(&self, frame: &impl Frame) -> Option<i64> {
+        if self.factor_den <= 0 {
+            return None;
+        }
+        let raw = extract_bits(frame.data(), self.start_bit, self.len, self.little_endian)?;
+        let scaled = i64::try_from(raw).ok()?.checked_mul(self.factor_num)?;
+        div_round(scaled, self.factor_den)?.checked_add(self.offset)
+    }
+
+    /// Encodes a physical value of the signal into a data frame.
+    ///
+    /// The value is rounded to the nearest raw value. This fails if the
+    /// signal does not fit inside the data of the frame, if the scale is
+    /// zero or has a denominator that is not positive, or if the raw value
+    /// can't be represented in the signal.
+    pub fn encode(&self, frame: &mut CanDataFrame, physical: i64) -> Result<(), ConstructionError> {
+        if self.factor_num == 0 || self.factor_den <= 0 {
+            return Err(ConstructionError::ValueOutOfRange);
+        }
+        let raw = physical
+            .checked_sub(self.offset)
+            .and_then(|n| n.checked_mul(self.factor_den))
+            .and_then(|n| match self.factor_num {
+                f if f < 0 => div_round(n.checked_neg()?, f.checked_neg()?),
+                f => div_round(n, f),
+            })
+            .ok_or(ConstructionError::ValueOutOfRange)?;
+
+        let max = if self.len >= 63 {
+            i64::MAX
+        } else {
+            (1i64 << self.len) - 1
+        };
+        if !(0..=max).contains(&raw) {
+            return Err(ConstructionError::ValueOutOfRange);
+        }
+        insert_bits(frame.data_mut(), self.start_bit, self.len, self.little_endian, raw as u64)
+            .ok_or(ConstructionError::TooMuchData)
+    }
+}